@@ -1,13 +1,41 @@
 mod subcommands;
 mod utils;
 
-use clap::{Parser, Subcommand};
-use subcommands::{Alias, AliasCommand};
-use utils::{ load_from_file, save_to_file, RealFileReader};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::{CompleteEnv, Shell};
+use subcommands::{
+    write_registration, Alias, AliasCommand, Config, ConfigCommand, Group, GroupCommand, TuiCommand,
+};
+use utils::{
+    load_merged, load_or_default, lock_path, save_to_file, scope_path, ConfigLock, Error,
+    OutputFormat, RealFileReader, Scope,
+};
 
 #[derive(Parser)]
 #[command(version, about)]
 struct Cli {
+    #[clap(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Config file, overrides RUST_CLI_CONFIG and the XDG default"
+    )]
+    config: Option<String>,
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        help = "Config layer to use, writes go to the user layer by default"
+    )]
+    scope: Option<Scope>,
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        default_value = "plain",
+        help = "Output format for list, show and search"
+    )]
+    output: OutputFormat,
     #[clap(subcommand)]
     command: Commands,
 }
@@ -19,17 +47,112 @@ enum Commands {
         #[clap(subcommand)]
         subcommand: Alias,
     },
+    #[clap(about = "Group usage")]
+    Group {
+        #[clap(subcommand)]
+        subcommand: Group,
+    },
+    #[clap(about = "Config usage")]
+    Config {
+        #[clap(subcommand)]
+        subcommand: Config,
+    },
+    #[clap(about = "Manage aliases in a terminal UI")]
+    Tui,
+    #[clap(about = "Print the shell completion script")]
+    Completions {
+        #[clap(value_enum)]
+        shell: Shell,
+    },
 }
 
-fn main() {
-    let cli = Cli::parse();
-    const FILE_PATH: &str = "config.toml";
-    let toml_config = load_from_file(&RealFileReader, FILE_PATH.to_string());
+impl Commands {
+    fn writes_config(&self) -> bool {
+        match self {
+            Commands::Alias { subcommand } => subcommand.writes_config(),
+            Commands::Group { subcommand } => subcommand.writes_config(),
+            Commands::Tui => true,
+            Commands::Config { subcommand } => subcommand.writes_config(),
+            Commands::Completions { .. } => false,
+        }
+    }
+}
+
+fn run(cli: &Cli) -> Result<i32, Error> {
+    let config = cli.config.as_deref();
+    let writes = cli.command.writes_config();
+    // Writes go to a single layer so aliases from the other layers are never
+    // copied into it; reads see all layers merged unless a scope is chosen.
+    let file_path = scope_path(cli.scope.unwrap_or(Scope::User), config);
+    let _lock = if writes {
+        let lock = ConfigLock::acquire(&file_path).map_err(|source| Error::Io {
+            path: lock_path(&file_path),
+            source,
+        })?;
+        Some(lock)
+    } else {
+        None
+    };
+    let load = || {
+        if writes || cli.scope.is_some() {
+            load_or_default(&RealFileReader, file_path.clone())
+        } else {
+            load_merged(&RealFileReader, config)
+        }
+    };
+    let save = |toml_config| {
+        if writes {
+            save_to_file(&RealFileReader, file_path.clone(), toml_config)
+        } else {
+            Ok(())
+        }
+    };
     match &cli.command {
         Commands::Alias { subcommand } => {
-            let mut command = AliasCommand::new(subcommand.clone(), toml_config.unwrap());
-            command.run();
-            save_to_file(&RealFileReader, FILE_PATH.to_string(), &command.toml_config).unwrap();
+            let toml_config = load()?;
+            let mut command = AliasCommand::new(subcommand.clone(), toml_config);
+            command.output = cli.output;
+            let result = command.run();
+            save(&command.toml_config)?;
+            result
+        }
+        Commands::Group { subcommand } => {
+            let toml_config = load()?;
+            let mut command = GroupCommand::new(subcommand.clone(), toml_config);
+            command.output = cli.output;
+            let result = command.run();
+            save(&command.toml_config)?;
+            result
+        }
+        Commands::Config { subcommand } => {
+            let mut command = ConfigCommand::new(
+                subcommand.clone(),
+                Box::new(RealFileReader),
+                file_path.clone(),
+            );
+            command.run()
+        }
+        Commands::Tui => {
+            let toml_config = load()?;
+            let mut command =
+                TuiCommand::new(toml_config, Box::new(RealFileReader), file_path.clone());
+            let result = command.run();
+            save(&command.toml_config)?;
+            result
         }
+        Commands::Completions { shell } => write_registration(*shell),
     }
 }
+
+fn main() {
+    CompleteEnv::with_factory(Cli::command).complete();
+    let cli = Cli::parse();
+    let code = match run(&cli) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            err.exit_code()
+        }
+    };
+    std::process::exit(code);
+}
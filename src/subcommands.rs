@@ -1,3 +1,11 @@
 mod alias;
+mod completion;
+mod config;
+mod group;
+mod tui;
 
 pub use alias::*;
+pub use completion::*;
+pub use config::*;
+pub use group::*;
+pub use tui::*;
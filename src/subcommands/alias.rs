@@ -1,5 +1,19 @@
-use crate::utils::TomlConfig;
+use crate::subcommands::{alias_candidates, group_candidates};
+use crate::utils::{
+    expand_home, parse_alias_line, AliasEntry, AliasTable, Error, MatchMode, OnConflict,
+    OutputFormat, RunOptions, ShellKind, TemplateArgs, TomlConfig,
+};
 use clap::Subcommand;
+use clap_complete::ArgValueCandidates;
+use std::collections::HashMap;
+
+fn parse_key_value(param: &str) -> Result<(String, String), String> {
+    match param.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("invalid value '{}', expected KEY=VALUE", param)),
+    }
+}
+
 #[derive(Clone, Subcommand)]
 pub enum Alias {
     #[clap(about = "Add alias")]
@@ -8,40 +22,164 @@ pub enum Alias {
         alias: String,
         #[clap(short, long)]
         command: String,
-        #[clap(short, long, required = false)]
+        #[clap(short, long, required = false, add = ArgValueCandidates::new(group_candidates))]
         group: Option<String>,
+        #[clap(
+            short,
+            long,
+            value_name = "KEY=VALUE",
+            value_parser = parse_key_value,
+            help = "Environment variable set when the alias runs"
+        )]
+        env: Vec<(String, String)>,
+        #[clap(long, help = "Working directory the alias runs in")]
+        cwd: Option<String>,
+        #[clap(long, help = "Start the alias with an empty environment")]
+        clear_env: bool,
+        #[clap(short, long, help = "What the alias does")]
+        description: Option<String>,
+        #[clap(short, long = "tag", help = "Tag used to organize aliases")]
+        tags: Vec<String>,
+        #[clap(long)]
+        author: Option<String>,
     },
     #[clap(about = "Remove alias")]
     Remove {
-        #[clap(short, long)]
+        #[clap(short, long, add = ArgValueCandidates::new(alias_candidates))]
         alias: String,
-        #[clap(short, long, required = false)]
+        #[clap(short, long, required = false, add = ArgValueCandidates::new(group_candidates))]
         group: Option<String>,
     },
+    #[clap(about = "Rename alias")]
+    Rename {
+        #[clap(short, long, add = ArgValueCandidates::new(alias_candidates))]
+        alias: String,
+        #[clap(short, long, required = false, add = ArgValueCandidates::new(group_candidates))]
+        group: Option<String>,
+        #[clap(long, help = "New alias name")]
+        to: String,
+        #[clap(long, help = "Replace an existing alias with the same name")]
+        overwrite: bool,
+    },
+    #[clap(about = "Move alias to another group")]
+    Mv {
+        #[clap(short, long, add = ArgValueCandidates::new(alias_candidates))]
+        alias: String,
+        #[clap(short, long, required = false, add = ArgValueCandidates::new(group_candidates))]
+        group: Option<String>,
+        #[clap(long, help = "Destination group, general aliases if omitted", add = ArgValueCandidates::new(group_candidates))]
+        to_group: Option<String>,
+        #[clap(long, help = "Replace an existing alias with the same name")]
+        overwrite: bool,
+    },
+    #[clap(about = "Copy alias")]
+    Cp {
+        #[clap(short, long, add = ArgValueCandidates::new(alias_candidates))]
+        alias: String,
+        #[clap(short, long, required = false, add = ArgValueCandidates::new(group_candidates))]
+        group: Option<String>,
+        #[clap(long, help = "Name of the copy, same name if omitted")]
+        to: Option<String>,
+        #[clap(long, help = "Destination group, general aliases if omitted", add = ArgValueCandidates::new(group_candidates))]
+        to_group: Option<String>,
+        #[clap(long, help = "Replace an existing alias with the same name")]
+        overwrite: bool,
+    },
     #[clap(about = "List aliases")]
     List {
-        #[clap(short, long, required = false)]
+        #[clap(short, long, required = false, add = ArgValueCandidates::new(group_candidates))]
+        group: Option<String>,
+    },
+    #[clap(about = "Search aliases in all groups")]
+    Search {
+        #[clap(help = "Text matched against alias names, commands and tags")]
+        pattern: String,
+        #[clap(short, long, value_enum, default_value_t = MatchMode::Substring)]
+        mode: MatchMode,
+    },
+    #[clap(about = "Print shell definitions for aliases")]
+    Export {
+        #[clap(short, long, value_enum)]
+        shell: ShellKind,
+        #[clap(
+            short,
+            long,
+            required = false,
+            help = "Only export aliases in this group",
+            add = ArgValueCandidates::new(group_candidates)
+        )]
+        group: Option<String>,
+        #[clap(long, help = "Prefix group aliases with their group name")]
+        prefix: bool,
+    },
+    #[clap(about = "Import aliases from a shell rc file")]
+    Import {
+        #[clap(long, help = "Shell rc file to read aliases from")]
+        from: String,
+        #[clap(
+            short,
+            long,
+            value_enum,
+            help = "Syntax of the file, fish for *.fish files and bash otherwise"
+        )]
+        shell: Option<ShellKind>,
+        #[clap(short, long, required = false, add = ArgValueCandidates::new(group_candidates))]
         group: Option<String>,
+        #[clap(long, value_enum, default_value_t = OnConflict::Skip)]
+        on_conflict: OnConflict,
     },
     #[clap(about = "Show alias")]
     Show {
-        #[clap(short, long)]
+        #[clap(short, long, add = ArgValueCandidates::new(alias_candidates))]
         alias: String,
-        #[clap(short, long, required = false)]
+        #[clap(short, long, required = false, add = ArgValueCandidates::new(group_candidates))]
         group: Option<String>,
     },
     #[clap(about = "Execute alias")]
     Exec {
-        #[clap(short, long)]
-        alias: String,
-        #[clap(short, long, required = false)]
+        #[clap(short, long, help = "Alias to run, picked interactively if omitted", add = ArgValueCandidates::new(alias_candidates))]
+        alias: Option<String>,
+        #[clap(short, long, required = false, add = ArgValueCandidates::new(group_candidates))]
         group: Option<String>,
+        #[clap(
+            short,
+            long = "param",
+            value_name = "KEY=VALUE",
+            value_parser = parse_key_value,
+            help = "Value for a named placeholder"
+        )]
+        params: Vec<(String, String)>,
+        #[clap(value_name = "VALUE", help = "Values for positional placeholders")]
+        values: Vec<String>,
+        #[clap(long, help = "Buffer output and print it after the command exits")]
+        capture: bool,
+        #[clap(
+            last = true,
+            value_name = "ARGS",
+            help = "Arguments appended to the command"
+        )]
+        extra: Vec<String>,
     },
 }
 
+impl Alias {
+    pub fn writes_config(&self) -> bool {
+        matches!(
+            self,
+            Alias::Add { .. }
+                | Alias::Remove { .. }
+                | Alias::Rename { .. }
+                | Alias::Mv { .. }
+                | Alias::Cp { .. }
+                | Alias::Import { .. }
+        )
+    }
+}
+
 pub struct AliasCommand {
     pub subcommand: Alias,
     pub toml_config: TomlConfig,
+    pub output: OutputFormat,
 }
 
 impl AliasCommand {
@@ -49,58 +187,203 @@ impl AliasCommand {
         AliasCommand {
             subcommand,
             toml_config,
+            output: OutputFormat::default(),
         }
     }
-    pub fn command_factory(&mut self) {
+    pub fn command_factory(&mut self) -> Result<i32, Error> {
         let subcommand = self.subcommand.clone();
         match &subcommand {
             Alias::Add {
                 alias,
                 command,
                 group,
+                env,
+                cwd,
+                clear_env,
+                description,
+                tags,
+                author,
             } => {
-                self.add(alias.as_str(), command.as_str(), group.as_deref());
+                let entry = AliasEntry::Table(Box::new(AliasTable {
+                    command: Some(command.clone()),
+                    description: description.clone(),
+                    tags: tags.clone(),
+                    author: author.clone(),
+                    env: env.iter().cloned().collect(),
+                    cwd: cwd.clone(),
+                    clear_env: *clear_env,
+                    ..AliasTable::default()
+                }));
+                self.add(alias.as_str(), entry, group.as_deref())?;
+                Ok(0)
             }
             Alias::Remove { alias, group } => {
-                self.remove(alias.as_str(), group.as_deref());
+                self.remove(alias.as_str(), group.as_deref())?;
+                Ok(0)
+            }
+            Alias::Rename {
+                alias,
+                group,
+                to,
+                overwrite,
+            } => {
+                self.toml_config
+                    .rename(alias, group.as_deref(), to, *overwrite)?;
+                Ok(0)
+            }
+            Alias::Mv {
+                alias,
+                group,
+                to_group,
+                overwrite,
+            } => {
+                self.toml_config.move_to(
+                    alias,
+                    group.as_deref(),
+                    to_group.as_deref(),
+                    *overwrite,
+                )?;
+                Ok(0)
+            }
+            Alias::Cp {
+                alias,
+                group,
+                to,
+                to_group,
+                overwrite,
+            } => {
+                let to = to.as_deref().unwrap_or(alias);
+                self.toml_config.copy(
+                    (alias, group.as_deref()),
+                    (to, to_group.as_deref()),
+                    *overwrite,
+                )?;
+                Ok(0)
             }
             Alias::List { group } => {
-                self.list(group.as_deref());
+                self.list(group.as_deref())?;
+                Ok(0)
+            }
+            Alias::Search { pattern, mode } => {
+                self.toml_config.search(pattern, *mode, self.output)?;
+                Ok(0)
+            }
+            Alias::Export {
+                shell,
+                group,
+                prefix,
+            } => {
+                print!(
+                    "{}",
+                    self.toml_config.export(*shell, group.as_deref(), *prefix)?
+                );
+                Ok(0)
+            }
+            Alias::Import {
+                from,
+                shell,
+                group,
+                on_conflict,
+            } => {
+                let path = expand_home(from);
+                let content = std::fs::read_to_string(&path).map_err(|source| Error::Io {
+                    path: path.display().to_string(),
+                    source,
+                })?;
+                let shell = shell.unwrap_or(if from.ends_with(".fish") {
+                    ShellKind::Fish
+                } else {
+                    ShellKind::Bash
+                });
+                let mut aliases = Vec::new();
+                for (index, line) in content.lines().enumerate() {
+                    match parse_alias_line(line, shell) {
+                        Some(Ok(definitions)) => aliases.extend(definitions),
+                        Some(Err(reason)) => {
+                            eprintln!("{}:{}: skipped: {}", from, index + 1, reason)
+                        }
+                        None => {}
+                    }
+                }
+                let (imported, report) =
+                    self.toml_config
+                        .import(&aliases, group.as_deref(), *on_conflict)?;
+                for line in &report {
+                    eprintln!("{}", line);
+                }
+                println!("Imported {} alias(es)", imported);
+                Ok(0)
             }
             Alias::Show { alias, group } => {
-                self.show(alias.as_str(), group.as_deref());
+                self.show(alias.as_str(), group.as_deref())?;
+                Ok(0)
             }
-            Alias::Exec { alias, group } => {
-                self.execute(alias.as_str(), group.as_deref());
+            Alias::Exec {
+                alias,
+                group,
+                params,
+                values,
+                capture,
+                extra,
+            } => {
+                let args = TemplateArgs {
+                    positional: values.clone(),
+                    named: params.iter().cloned().collect::<HashMap<_, _>>(),
+                    trailing: extra.clone(),
+                };
+                let options = RunOptions {
+                    capture: *capture,
+                    ..RunOptions::default()
+                };
+                let alias = match alias {
+                    Some(alias) => alias.clone(),
+                    None => match self.toml_config.pick(group.as_deref())? {
+                        Some(alias) => alias,
+                        None => return Ok(130),
+                    },
+                };
+                self.execute(alias.as_str(), group.as_deref(), &args, &options)
             }
-        };
+        }
     }
 
-    pub fn run(&mut self) -> () {
+    pub fn run(&mut self) -> Result<i32, Error> {
         self.command_factory()
     }
 
-    fn add(&mut self, alias: &str, command: &str, group_name: Option<&str>) -> () {
-        self.toml_config.add(alias, command, group_name);
+    fn add(
+        &mut self,
+        alias: &str,
+        entry: AliasEntry,
+        group_name: Option<&str>,
+    ) -> Result<(), Error> {
+        self.toml_config.add(alias, entry, group_name)
     }
 
-    fn remove(&mut self, alias: &str, group_name: Option<&str>) -> () {
-        self.toml_config.remove(alias, group_name);
+    fn remove(&mut self, alias: &str, group_name: Option<&str>) -> Result<(), Error> {
+        self.toml_config.remove(alias, group_name)
     }
 
-    fn list(&mut self, group_name: Option<&str>) -> () {
-        self.toml_config.list(group_name);
+    fn list(&mut self, group_name: Option<&str>) -> Result<(), Error> {
+        self.toml_config.list(group_name, self.output)
     }
 
-    fn show(&mut self, alias: &str, group_name: Option<&str>) -> () {
-        self.toml_config.show(alias, group_name);
+    fn show(&mut self, alias: &str, group_name: Option<&str>) -> Result<(), Error> {
+        self.toml_config.show(alias, group_name, self.output)
     }
 
-    fn execute(&mut self, alias: &str, group_name: Option<&str>) -> () {
-        self.toml_config.execute(alias, group_name);
+    fn execute(
+        &mut self,
+        alias: &str,
+        group_name: Option<&str>,
+        args: &TemplateArgs,
+        options: &RunOptions,
+    ) -> Result<i32, Error> {
+        self.toml_config.execute(alias, group_name, args, options)
     }
 }
 
+#[cfg(test)]
 mod test {
     use super::super::super::utils::TomlConfig;
     use super::*;
@@ -131,10 +414,16 @@ mod test {
                 alias: "test_alias".to_string(),
                 command: "test_command".to_string(),
                 group: None,
+                env: vec![],
+                cwd: None,
+                clear_env: false,
+                description: None,
+                tags: vec![],
+                author: None,
             },
             toml_config,
         );
-        alias_command.run();
+        alias_command.run().unwrap();
         assert!(alias_command.toml_config.contains("test_alias", None));
     }
 
@@ -146,10 +435,16 @@ mod test {
                 alias: "test_alias".to_string(),
                 command: "test_command".to_string(),
                 group: Some("test_group".to_string()),
+                env: vec![],
+                cwd: None,
+                clear_env: false,
+                description: None,
+                tags: vec![],
+                author: None,
             },
             toml_config,
         );
-        alias_command.run();
+        alias_command.run().unwrap();
         assert!(alias_command
             .toml_config
             .contains("test_alias", Some("test_group")));
@@ -163,15 +458,21 @@ mod test {
                 alias: "test_alias".to_string(),
                 command: "test_command".to_string(),
                 group: None,
+                env: vec![],
+                cwd: None,
+                clear_env: false,
+                description: None,
+                tags: vec![],
+                author: None,
             },
             toml_config,
         );
-        alias_command.run();
+        alias_command.run().unwrap();
         alias_command.subcommand = Alias::Remove {
             alias: "test_alias".to_string(),
             group: None,
         };
-        alias_command.run();
+        alias_command.run().unwrap();
         assert!(!alias_command.toml_config.contains("test_alias", None));
     }
 
@@ -183,16 +484,22 @@ mod test {
                 alias: "test_alias".to_string(),
                 command: "test_command".to_string(),
                 group: Some("test_group".to_string()),
+                env: vec![],
+                cwd: None,
+                clear_env: false,
+                description: None,
+                tags: vec![],
+                author: None,
             },
             toml_config,
         );
-        alias_command.run();
+        alias_command.run().unwrap();
         alias_command.subcommand = Alias::Remove {
             alias: "test_alias".to_string(),
             group: Some("test_group".to_string()),
         };
-        alias_command.run();
-        alias_command.run();
+        alias_command.run().unwrap();
+        assert!(alias_command.run().is_err());
         assert!(!alias_command
             .toml_config
             .contains("test_alias", Some("test_group")));
@@ -201,13 +508,8 @@ mod test {
     #[test]
     fn test_list() {
         let toml_config = mock_toml_config();
-        let mut alias_command = AliasCommand::new(
-            Alias::List {
-                group: None,
-            },
-            toml_config,
-        );
-        alias_command.run();
+        let mut alias_command = AliasCommand::new(Alias::List { group: None }, toml_config);
+        alias_command.run().unwrap();
     }
 
     #[test]
@@ -219,7 +521,10 @@ mod test {
             },
             toml_config,
         );
-        alias_command.run();
+        assert!(matches!(
+            alias_command.run(),
+            Err(Error::GroupNotFound { .. })
+        ));
     }
 
     #[test]
@@ -232,7 +537,10 @@ mod test {
             },
             toml_config,
         );
-        alias_command.run();
+        assert!(matches!(
+            alias_command.run(),
+            Err(Error::AliasNotFound { .. })
+        ));
     }
 
     #[test]
@@ -245,7 +553,10 @@ mod test {
             },
             toml_config,
         );
-        alias_command.run();
+        assert!(matches!(
+            alias_command.run(),
+            Err(Error::GroupNotFound { .. })
+        ));
     }
 
     #[test]
@@ -253,12 +564,19 @@ mod test {
         let toml_config = mock_toml_config();
         let mut alias_command = AliasCommand::new(
             Alias::Exec {
-                alias: "test_alias".to_string(),
+                alias: Some("test_alias".to_string()),
                 group: None,
+                params: vec![],
+                values: vec![],
+                capture: false,
+                extra: vec![],
             },
             toml_config,
         );
-        alias_command.run();
+        assert!(matches!(
+            alias_command.run(),
+            Err(Error::AliasNotFound { .. })
+        ));
     }
 
     #[test]
@@ -266,11 +584,110 @@ mod test {
         let toml_config = mock_toml_config();
         let mut alias_command = AliasCommand::new(
             Alias::Exec {
-                alias: "test_alias".to_string(),
+                alias: Some("test_alias".to_string()),
                 group: Some("test_group".to_string()),
+                params: vec![],
+                values: vec![],
+                capture: false,
+                extra: vec![],
             },
             toml_config,
         );
-        alias_command.run();
+        assert!(matches!(
+            alias_command.run(),
+            Err(Error::GroupNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_key_value() {
+        assert_eq!(
+            parse_key_value("task=abc=1").unwrap(),
+            ("task".to_string(), "abc=1".to_string())
+        );
+        assert!(parse_key_value("task").is_err());
+        assert!(parse_key_value("=abc").is_err());
+    }
+
+    #[test]
+    fn test_add_with_env_and_cwd() {
+        let toml_config = mock_toml_config();
+        let mut alias_command = AliasCommand::new(
+            Alias::Add {
+                alias: "deploy".to_string(),
+                command: "make deploy".to_string(),
+                group: None,
+                env: vec![("AWS_PROFILE".to_string(), "dev".to_string())],
+                cwd: Some("~/src/infra".to_string()),
+                clear_env: false,
+                description: Some("Deploy infra".to_string()),
+                tags: vec!["aws".to_string()],
+                author: None,
+            },
+            toml_config,
+        );
+        alias_command.run().unwrap();
+        assert!(alias_command.toml_config.contains("deploy", None));
+        alias_command.subcommand = Alias::Show {
+            alias: "deploy".to_string(),
+            group: None,
+        };
+        alias_command.run().unwrap();
+    }
+
+    #[test]
+    fn test_rename_mv_cp() {
+        let toml_config = mock_toml_config();
+        let mut alias_command = AliasCommand::new(
+            Alias::Rename {
+                alias: "ls".to_string(),
+                group: None,
+                to: "list".to_string(),
+                overwrite: false,
+            },
+            toml_config,
+        );
+        alias_command.run().unwrap();
+        alias_command.subcommand = Alias::Mv {
+            alias: "list".to_string(),
+            group: None,
+            to_group: Some("test_group".to_string()),
+            overwrite: false,
+        };
+        alias_command.run().unwrap();
+        alias_command.subcommand = Alias::Cp {
+            alias: "list".to_string(),
+            group: Some("test_group".to_string()),
+            to: None,
+            to_group: None,
+            overwrite: false,
+        };
+        alias_command.run().unwrap();
+        assert!(alias_command.toml_config.contains("list", None));
+        assert!(alias_command
+            .toml_config
+            .contains("list", Some("test_group")));
+        assert!(matches!(
+            alias_command.run(),
+            Err(Error::AliasExists { .. })
+        ));
+    }
+
+    #[test]
+    fn test_search() {
+        let toml_config = mock_toml_config();
+        let mut alias_command = AliasCommand::new(
+            Alias::Search {
+                pattern: "ls".to_string(),
+                mode: MatchMode::Fuzzy,
+            },
+            toml_config,
+        );
+        alias_command.run().unwrap();
+        alias_command.subcommand = Alias::Search {
+            pattern: "(".to_string(),
+            mode: MatchMode::Regex,
+        };
+        assert!(matches!(alias_command.run(), Err(Error::InvalidPattern(_))));
     }
 }
@@ -0,0 +1,114 @@
+use crate::utils::{load_merged, Error, RealFileReader, TomlConfig};
+use clap::builder::StyledStr;
+use clap_complete::env::Shells;
+use clap_complete::{CompletionCandidate, Shell};
+use std::io;
+
+// Completion runs without the rest of the command line, so `--config` is not
+// available here and only the environment decides which file is read.
+fn load() -> Option<TomlConfig> {
+    load_merged(&RealFileReader, None).ok()
+}
+
+// Names of every alias with its command as help. Group aliases are also
+// offered as `group/alias`, hidden until the group has been typed.
+pub fn alias_candidates() -> Vec<CompletionCandidate> {
+    load().map_or_else(Vec::new, |toml_config| aliases(&toml_config))
+}
+
+pub fn group_candidates() -> Vec<CompletionCandidate> {
+    load().map_or_else(Vec::new, |toml_config| groups(&toml_config))
+}
+
+fn aliases(toml_config: &TomlConfig) -> Vec<CompletionCandidate> {
+    let mut names = Vec::new();
+    let mut candidates = Vec::new();
+    for (group, alias, entry) in toml_config.entries() {
+        let help = Some(StyledStr::from(entry.to_string()));
+        if let Some(group) = group {
+            candidates.push(
+                CompletionCandidate::new(format!("{}/{}", group, alias))
+                    .help(help.clone())
+                    .hide(true),
+            );
+        }
+        if !names.contains(&alias) {
+            names.push(alias);
+            candidates.push(CompletionCandidate::new(alias).help(help));
+        }
+    }
+    candidates
+}
+
+fn groups(toml_config: &TomlConfig) -> Vec<CompletionCandidate> {
+    toml_config
+        .group_names()
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+pub fn write_registration(shell: Shell) -> Result<i32, Error> {
+    let name = env!("CARGO_PKG_NAME");
+    let io_error = |source| Error::Io {
+        path: "stdout".to_string(),
+        source,
+    };
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(&shell.to_string())
+        .ok_or_else(|| io_error(io::Error::other(format!("{} is not supported", shell))))?;
+    completer
+        .write_registration("COMPLETE", name, name, name, &mut io::stdout())
+        .map_err(io_error)?;
+    Ok(0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn toml_config() -> TomlConfig {
+        toml::from_str(
+            r#"
+            [alias.general]
+            ls = "ls -l"
+            [alias.group.aws]
+            ls = "aws s3 ls"
+            whoami = "aws sts get-caller-identity"
+            [alias.group.k8s]
+        "#,
+        )
+        .unwrap()
+    }
+
+    fn candidate(value: &str, help: &'static str, hidden: bool) -> CompletionCandidate {
+        CompletionCandidate::new(value)
+            .help(Some(StyledStr::from(help)))
+            .hide(hidden)
+    }
+
+    #[test]
+    fn test_aliases() {
+        assert_eq!(
+            aliases(&toml_config()),
+            vec![
+                candidate("ls", "ls -l", false),
+                candidate("aws/ls", "aws s3 ls", true),
+                candidate("aws/whoami", "aws sts get-caller-identity", true),
+                candidate("whoami", "aws sts get-caller-identity", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_groups() {
+        assert_eq!(
+            groups(&toml_config()),
+            vec![
+                CompletionCandidate::new("aws"),
+                CompletionCandidate::new("k8s")
+            ]
+        );
+    }
+}
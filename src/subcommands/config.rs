@@ -0,0 +1,161 @@
+use crate::utils::{Error, FileReader};
+use clap::Subcommand;
+use std::io;
+
+const STARTER_CONFIG: &str = r#"# rust_cli config
+#
+# Aliases outside any group go in [alias.general]. An alias is either a
+# command string or a table with more details:
+#
+#   ll = "ls -al"
+#
+#   [alias.general.deploy]
+#   command = "make deploy ENV={env:-staging}"
+#   description = "Deploy the current branch"
+#   tags = ["ops"]
+#
+# Grouped aliases go in [alias.group.<name>]:
+#
+#   [alias.group.aws]
+#   whoami = "aws sts get-caller-identity"
+
+[settings]
+# Run the closest alias when the one asked for does not exist.
+auto_run_suggestion = false
+# Previous versions of this file kept as .bak.1, .bak.2, ...
+backups = 3
+
+[alias.general]
+"#;
+
+#[derive(Clone, Subcommand)]
+pub enum Config {
+    #[clap(about = "Print the path of the config file in use")]
+    Path,
+    #[clap(about = "Write a commented starter config file")]
+    Init {
+        #[clap(long, help = "Overwrite an existing config file")]
+        force: bool,
+    },
+}
+
+impl Config {
+    pub fn writes_config(&self) -> bool {
+        matches!(self, Config::Init { .. })
+    }
+}
+
+pub struct ConfigCommand {
+    pub subcommand: Config,
+    pub file_reader: Box<dyn FileReader>,
+    pub file_path: String,
+}
+
+impl ConfigCommand {
+    pub fn new(subcommand: Config, file_reader: Box<dyn FileReader>, file_path: String) -> Self {
+        ConfigCommand {
+            subcommand,
+            file_reader,
+            file_path,
+        }
+    }
+
+    pub fn command_factory(&mut self) -> Result<i32, Error> {
+        match &self.subcommand {
+            Config::Path => println!("{}", self.file_path),
+            Config::Init { force } => self.init(*force)?,
+        }
+        Ok(0)
+    }
+
+    fn init(&self, force: bool) -> Result<(), Error> {
+        if !force {
+            match self.file_reader.read_to_string(&self.file_path) {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(source) => {
+                    return Err(Error::Io {
+                        path: self.file_path.clone(),
+                        source,
+                    })
+                }
+                Ok(_) => return Err(Error::ConfigExists(self.file_path.clone())),
+            }
+        }
+        self.file_reader
+            .write(&self.file_path, STARTER_CONFIG)
+            .map_err(|source| Error::Io {
+                path: self.file_path.clone(),
+                source,
+            })?;
+        println!("Created {}", self.file_path);
+        Ok(())
+    }
+
+    pub fn run(&mut self) -> Result<i32, Error> {
+        self.command_factory()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::{MockFileReader, TomlConfig};
+    use mockall::predicate::eq;
+
+    #[test]
+    fn test_path() {
+        let mut config_command = ConfigCommand::new(
+            Config::Path,
+            Box::new(MockFileReader::new()),
+            "config.toml".to_string(),
+        );
+        assert_eq!(config_command.run().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_init() {
+        let mut mock = MockFileReader::new();
+        mock.expect_read_to_string()
+            .with(eq("config.toml"))
+            .returning(|_| Err(io::Error::from(io::ErrorKind::NotFound)));
+        mock.expect_write()
+            .with(eq("config.toml"), eq(STARTER_CONFIG))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let mut config_command = ConfigCommand::new(
+            Config::Init { force: false },
+            Box::new(mock),
+            "config.toml".to_string(),
+        );
+        assert_eq!(config_command.run().unwrap(), 0);
+        let toml_config: TomlConfig = toml::from_str(STARTER_CONFIG).unwrap();
+        assert!(toml_config.entries().is_empty());
+    }
+
+    #[test]
+    fn test_init_existing() {
+        let mut mock = MockFileReader::new();
+        mock.expect_read_to_string()
+            .returning(|_| Ok(String::new()));
+        mock.expect_write().never();
+        let mut config_command = ConfigCommand::new(
+            Config::Init { force: false },
+            Box::new(mock),
+            "config.toml".to_string(),
+        );
+        assert!(matches!(config_command.run(), Err(Error::ConfigExists(_))));
+    }
+    #[test]
+    fn test_init_unreadable() {
+        let mut mock = MockFileReader::new();
+        mock.expect_read_to_string()
+            .returning(|_| Err(io::Error::from(io::ErrorKind::PermissionDenied)));
+        mock.expect_write().never();
+        let mut config_command = ConfigCommand::new(
+            Config::Init { force: false },
+            Box::new(mock),
+            "config.toml".to_string(),
+        );
+        assert!(matches!(config_command.run(), Err(Error::Io { .. })));
+    }
+}
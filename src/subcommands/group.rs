@@ -0,0 +1,149 @@
+use crate::subcommands::group_candidates;
+use crate::utils::{Error, OutputFormat, TomlConfig};
+use clap::Subcommand;
+use clap_complete::ArgValueCandidates;
+
+#[derive(Clone, Subcommand)]
+pub enum Group {
+    #[clap(about = "List groups")]
+    List,
+    #[clap(about = "Create group")]
+    Create {
+        #[clap(short, long)]
+        group: String,
+    },
+    #[clap(about = "Delete group")]
+    Delete {
+        #[clap(short, long, add = ArgValueCandidates::new(group_candidates))]
+        group: String,
+        #[clap(long, help = "Delete the group even if it still has aliases")]
+        force: bool,
+    },
+    #[clap(about = "Rename group")]
+    Rename {
+        #[clap(short, long, add = ArgValueCandidates::new(group_candidates))]
+        group: String,
+        #[clap(long)]
+        to: String,
+    },
+    #[clap(about = "Show group")]
+    Show {
+        #[clap(short, long, add = ArgValueCandidates::new(group_candidates))]
+        group: String,
+    },
+}
+
+impl Group {
+    pub fn writes_config(&self) -> bool {
+        !matches!(self, Group::List | Group::Show { .. })
+    }
+}
+
+pub struct GroupCommand {
+    pub subcommand: Group,
+    pub toml_config: TomlConfig,
+    pub output: OutputFormat,
+}
+
+impl GroupCommand {
+    pub fn new(subcommand: Group, toml_config: TomlConfig) -> Self {
+        GroupCommand {
+            subcommand,
+            toml_config,
+            output: OutputFormat::default(),
+        }
+    }
+
+    pub fn command_factory(&mut self) -> Result<i32, Error> {
+        let subcommand = self.subcommand.clone();
+        match &subcommand {
+            Group::List => self.toml_config.list_groups(self.output)?,
+            Group::Create { group } => self.toml_config.create_group(group)?,
+            Group::Delete { group, force } => self.toml_config.delete_group(group, *force)?,
+            Group::Rename { group, to } => self.toml_config.rename_group(group, to)?,
+            Group::Show { group } => self.toml_config.list(Some(group), self.output)?,
+        }
+        Ok(0)
+    }
+
+    pub fn run(&mut self) -> Result<i32, Error> {
+        self.command_factory()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn mock_toml_config() -> TomlConfig {
+        let content = r#"
+            [alias.general]
+            "ls" = "ls -l"
+            [alias.group.aws]
+            "aws_help" = "aws --help"
+        "#;
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_create_and_delete() {
+        let mut group_command = GroupCommand::new(
+            Group::Create {
+                group: "k8s".to_string(),
+            },
+            mock_toml_config(),
+        );
+        group_command.run().unwrap();
+        assert!(group_command.run().is_err());
+        group_command.subcommand = Group::Delete {
+            group: "k8s".to_string(),
+            force: false,
+        };
+        group_command.run().unwrap();
+        group_command.subcommand = Group::Show {
+            group: "k8s".to_string(),
+        };
+        assert!(matches!(
+            group_command.run(),
+            Err(Error::GroupNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_delete_non_empty_requires_force() {
+        let mut group_command = GroupCommand::new(
+            Group::Delete {
+                group: "aws".to_string(),
+                force: false,
+            },
+            mock_toml_config(),
+        );
+        assert!(matches!(
+            group_command.run(),
+            Err(Error::GroupNotEmpty { .. })
+        ));
+        group_command.subcommand = Group::Delete {
+            group: "aws".to_string(),
+            force: true,
+        };
+        group_command.run().unwrap();
+    }
+
+    #[test]
+    fn test_rename_and_list() {
+        let mut group_command = GroupCommand::new(
+            Group::Rename {
+                group: "aws".to_string(),
+                to: "amazon".to_string(),
+            },
+            mock_toml_config(),
+        );
+        group_command.run().unwrap();
+        group_command.subcommand = Group::List;
+        group_command.run().unwrap();
+        group_command.subcommand = Group::Show {
+            group: "amazon".to_string(),
+        };
+        group_command.run().unwrap();
+    }
+}
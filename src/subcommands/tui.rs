@@ -0,0 +1,630 @@
+use crate::utils::{
+    save_to_file, AliasEntry, AliasTable, Error, FileReader, TemplateArgs, TemplateError,
+    TomlConfig,
+};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Position};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, List, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::io::{self, IsTerminal};
+
+const HELP: &str =
+    "tab: switch pane  a: add  e: edit  d: delete  enter: run  R: run with arguments  q: quit";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Focus {
+    Groups,
+    Aliases,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Prompt {
+    AddName,
+    AddCommand { alias: String },
+    Edit { alias: String },
+    RunArgs { alias: String },
+}
+
+impl Prompt {
+    fn label(&self) -> &'static str {
+        match self {
+            Prompt::AddName => "New alias",
+            Prompt::AddCommand { .. } | Prompt::Edit { .. } => "Command",
+            Prompt::RunArgs { .. } => "Arguments (VALUE or KEY=VALUE)",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Mode {
+    Normal,
+    Input { prompt: Prompt, buffer: String },
+    ConfirmDelete { alias: String },
+}
+
+pub struct TuiCommand {
+    pub toml_config: TomlConfig,
+    file_reader: Box<dyn FileReader>,
+    file_path: String,
+    focus: Focus,
+    group_index: usize,
+    alias_index: usize,
+    mode: Mode,
+    output: Vec<String>,
+    status: String,
+    quit: bool,
+}
+
+fn parse_args(input: &str) -> TemplateArgs {
+    let mut args = TemplateArgs::default();
+    for token in input.split_whitespace() {
+        match token.split_once('=') {
+            Some((key, value)) if !key.is_empty() => {
+                args.named.insert(key.to_string(), value.to_string());
+            }
+            _ => args.positional.push(token.to_string()),
+        }
+    }
+    args
+}
+
+impl TuiCommand {
+    pub fn new(
+        toml_config: TomlConfig,
+        file_reader: Box<dyn FileReader>,
+        file_path: String,
+    ) -> Self {
+        TuiCommand {
+            toml_config,
+            file_reader,
+            file_path,
+            focus: Focus::Groups,
+            group_index: 0,
+            alias_index: 0,
+            mode: Mode::Normal,
+            output: Vec::new(),
+            status: String::new(),
+            quit: false,
+        }
+    }
+
+    pub fn run(&mut self) -> Result<i32, Error> {
+        if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+            return Err(Error::Terminal(io::Error::other("not a terminal")));
+        }
+        let mut terminal = ratatui::try_init().map_err(Error::Terminal)?;
+        let result = self.event_loop(&mut terminal);
+        ratatui::restore();
+        result.map(|_| 0)
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<(), Error> {
+        while !self.quit {
+            terminal
+                .draw(|frame| self.draw(frame))
+                .map_err(Error::Terminal)?;
+            if let Event::Key(key) = event::read().map_err(Error::Terminal)? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn groups(&self) -> Vec<Option<String>> {
+        let mut groups = vec![None];
+        groups.extend(self.toml_config.group_names().into_iter().map(Some));
+        groups
+    }
+
+    fn current_group(&self) -> Option<String> {
+        self.groups().get(self.group_index).cloned().flatten()
+    }
+
+    fn aliases(&self) -> Vec<String> {
+        self.toml_config
+            .alias_names(self.current_group().as_deref())
+    }
+
+    fn selected_alias(&self) -> Option<String> {
+        self.aliases().get(self.alias_index).cloned()
+    }
+
+    fn save(&self) -> Result<(), Error> {
+        save_to_file(
+            self.file_reader.as_ref(),
+            self.file_path.clone(),
+            &self.toml_config,
+        )
+    }
+
+    fn select_alias(&mut self, alias: &str) {
+        if let Some(index) = self.aliases().iter().position(|name| name == alias) {
+            self.alias_index = index;
+        }
+    }
+
+    fn clamp_selection(&mut self) {
+        self.group_index = self.group_index.min(self.groups().len() - 1);
+        self.alias_index = self.alias_index.min(self.aliases().len().saturating_sub(1));
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Result<(), Error> {
+        match self.mode.clone() {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::Input { prompt, mut buffer } => {
+                match key.code {
+                    KeyCode::Esc => self.mode = Mode::Normal,
+                    KeyCode::Enter => {
+                        self.mode = Mode::Normal;
+                        self.submit(prompt, buffer.trim())?;
+                    }
+                    KeyCode::Backspace => {
+                        buffer.pop();
+                        self.mode = Mode::Input { prompt, buffer };
+                    }
+                    KeyCode::Char(c) => {
+                        buffer.push(c);
+                        self.mode = Mode::Input { prompt, buffer };
+                    }
+                    _ => {}
+                }
+                Ok(())
+            }
+            Mode::ConfirmDelete { alias } => {
+                self.mode = Mode::Normal;
+                if key.code != KeyCode::Char('y') {
+                    self.status = "Delete cancelled".to_string();
+                    return Ok(());
+                }
+                let group = self.current_group();
+                match self.toml_config.remove(&alias, group.as_deref()) {
+                    Ok(()) => {
+                        self.save()?;
+                        self.status = format!("Deleted '{}'", alias);
+                    }
+                    Err(err) => self.status = err.to_string(),
+                }
+                self.clamp_selection();
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent) -> Result<(), Error> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if ctrl => self.quit = true,
+            KeyCode::Tab | KeyCode::Left | KeyCode::Right | KeyCode::Char('h' | 'l') => {
+                self.focus = match self.focus {
+                    Focus::Groups => Focus::Aliases,
+                    Focus::Aliases => Focus::Groups,
+                };
+            }
+            KeyCode::Up | KeyCode::Char('k') => match self.focus {
+                Focus::Groups => {
+                    self.group_index = self.group_index.saturating_sub(1);
+                    self.alias_index = 0;
+                }
+                Focus::Aliases => self.alias_index = self.alias_index.saturating_sub(1),
+            },
+            KeyCode::Down | KeyCode::Char('j') => {
+                match self.focus {
+                    Focus::Groups => {
+                        self.group_index += 1;
+                        self.alias_index = 0;
+                    }
+                    Focus::Aliases => self.alias_index += 1,
+                }
+                self.clamp_selection();
+            }
+            KeyCode::Char('a') => {
+                self.mode = Mode::Input {
+                    prompt: Prompt::AddName,
+                    buffer: String::new(),
+                };
+            }
+            KeyCode::Char('e') => {
+                let Some(alias) = self.selected_alias() else {
+                    return Ok(());
+                };
+                let group = self.current_group();
+                let Some(entry) = self.toml_config.find(&alias, group.as_deref()) else {
+                    return Ok(());
+                };
+                if entry.is_multi_step() {
+                    self.status =
+                        "Multi-step aliases can only be edited in the config file".to_string();
+                } else {
+                    self.mode = Mode::Input {
+                        prompt: Prompt::Edit { alias },
+                        buffer: entry
+                            .steps()
+                            .first()
+                            .map(|step| step.run().to_string())
+                            .unwrap_or_default(),
+                    };
+                }
+            }
+            KeyCode::Char('d') => {
+                if let Some(alias) = self.selected_alias() {
+                    self.mode = Mode::ConfirmDelete { alias };
+                }
+            }
+            KeyCode::Enter | KeyCode::Char('r') => {
+                if let Some(alias) = self.selected_alias() {
+                    self.run_alias(&alias, &TemplateArgs::default());
+                }
+            }
+            KeyCode::Char('R') => {
+                if let Some(alias) = self.selected_alias() {
+                    self.mode = Mode::Input {
+                        prompt: Prompt::RunArgs { alias },
+                        buffer: String::new(),
+                    };
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn submit(&mut self, prompt: Prompt, input: &str) -> Result<(), Error> {
+        let group = self.current_group();
+        match prompt {
+            Prompt::AddName if input.is_empty() => {}
+            Prompt::AddName if input.contains('/') => {
+                self.status = "Alias name must not contain '/'".to_string();
+            }
+            Prompt::AddName => {
+                self.mode = Mode::Input {
+                    prompt: Prompt::AddCommand {
+                        alias: input.to_string(),
+                    },
+                    buffer: String::new(),
+                };
+            }
+            Prompt::AddCommand { .. } | Prompt::Edit { .. } if input.is_empty() => {
+                self.status = "Command must not be empty".to_string();
+            }
+            Prompt::AddCommand { alias } => {
+                let entry = AliasEntry::Table(Box::new(AliasTable {
+                    command: Some(input.to_string()),
+                    ..AliasTable::default()
+                }));
+                self.toml_config.add(&alias, entry, group.as_deref())?;
+                self.save()?;
+                self.focus = Focus::Aliases;
+                self.select_alias(&alias);
+                self.status = format!("Added '{}'", alias);
+            }
+            Prompt::Edit { alias } => {
+                let Some(mut entry) = self.toml_config.find(&alias, group.as_deref()).cloned()
+                else {
+                    return Ok(());
+                };
+                match &mut entry {
+                    AliasEntry::Command(command) => *command = input.to_string(),
+                    AliasEntry::Table(table) => match table.steps.first_mut() {
+                        Some(step) if table.command.is_none() => step.set_run(input.to_string()),
+                        _ => table.command = Some(input.to_string()),
+                    },
+                }
+                self.toml_config.add(&alias, entry, group.as_deref())?;
+                self.save()?;
+                self.status = format!("Updated '{}'", alias);
+            }
+            Prompt::RunArgs { alias } => self.run_alias(&alias, &parse_args(input)),
+        }
+        Ok(())
+    }
+
+    fn run_alias(&mut self, alias: &str, args: &TemplateArgs) {
+        let group = self.current_group();
+        match self
+            .toml_config
+            .execute_captured(alias, group.as_deref(), args)
+        {
+            Ok(outputs) => {
+                self.output.clear();
+                for (step, output) in outputs {
+                    self.output.push(format!("$ {}", step.run()));
+                    self.output
+                        .extend(output.stdout.lines().map(str::to_string));
+                    self.output
+                        .extend(output.stderr.lines().map(str::to_string));
+                    self.output.push(format!("[exit {}]", output.code));
+                }
+                self.status = format!("Ran '{}'", alias);
+            }
+            Err(Error::Template(err @ TemplateError::Missing(_))) => {
+                self.status = err.to_string();
+                self.mode = Mode::Input {
+                    prompt: Prompt::RunArgs {
+                        alias: alias.to_string(),
+                    },
+                    buffer: String::new(),
+                };
+            }
+            Err(err) => self.status = err.to_string(),
+        }
+    }
+
+    fn pane(&self, title: &str, focus: Option<Focus>) -> Block<'static> {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title.to_string());
+        if focus.is_some() && focus == Some(self.focus) {
+            block.border_style(Style::default().add_modifier(Modifier::BOLD))
+        } else {
+            block
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, output, status] = Layout::vertical([
+            Constraint::Min(5),
+            Constraint::Length(10),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [groups, aliases, details] = Layout::horizontal([
+            Constraint::Length(20),
+            Constraint::Percentage(35),
+            Constraint::Min(20),
+        ])
+        .areas(main);
+        let highlight = Style::default().add_modifier(Modifier::REVERSED);
+
+        let group_names: Vec<String> = self
+            .groups()
+            .into_iter()
+            .map(|group| group.unwrap_or_else(|| "general".to_string()))
+            .collect();
+        let mut state = ListState::default().with_selected(Some(self.group_index));
+        frame.render_stateful_widget(
+            List::new(group_names)
+                .block(self.pane("Groups", Some(Focus::Groups)))
+                .highlight_style(highlight),
+            groups,
+            &mut state,
+        );
+
+        let alias_names = self.aliases();
+        let mut state = ListState::default()
+            .with_selected((!alias_names.is_empty()).then_some(self.alias_index));
+        frame.render_stateful_widget(
+            List::new(alias_names)
+                .block(self.pane("Aliases", Some(Focus::Aliases)))
+                .highlight_style(highlight),
+            aliases,
+            &mut state,
+        );
+
+        let group = self.current_group();
+        let mut lines: Vec<Line> = Vec::new();
+        if let Some(entry) = self
+            .selected_alias()
+            .and_then(|alias| self.toml_config.find(&alias, group.as_deref()))
+        {
+            for (index, step) in entry.steps().iter().enumerate() {
+                if entry.is_multi_step() {
+                    lines.push(Line::from(format!("{}. {}", index + 1, step)));
+                } else {
+                    lines.push(Line::from(step.to_string()));
+                }
+            }
+            lines.push(Line::from(""));
+            for (label, value) in entry.details() {
+                lines.push(Line::from(format!("{}: {}", label, value)));
+            }
+        }
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(self.pane("Details", None))
+                .wrap(Wrap { trim: false }),
+            details,
+        );
+
+        let visible = output.height.saturating_sub(2) as usize;
+        let scroll = self.output.len().saturating_sub(visible) as u16;
+        let output_lines: Vec<Line> = self
+            .output
+            .iter()
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+        frame.render_widget(
+            Paragraph::new(output_lines)
+                .block(self.pane("Output", None))
+                .scroll((scroll, 0)),
+            output,
+        );
+
+        let line = match &self.mode {
+            Mode::Normal if self.status.is_empty() => HELP.to_string(),
+            Mode::Normal => self.status.clone(),
+            Mode::Input { prompt, buffer } => {
+                let line = format!("{}: {}", prompt.label(), buffer);
+                frame.set_cursor_position(Position::new(
+                    status.x + line.chars().count() as u16,
+                    status.y,
+                ));
+                line
+            }
+            Mode::ConfirmDelete { alias } => format!("Delete '{}'? (y/n)", alias),
+        };
+        frame.render_widget(Paragraph::new(line), status);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::{load_from_file, MockFileReader, Step};
+    use mockall::predicate::eq;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn mock_toml_config() -> TomlConfig {
+        let content = r#"
+            [alias]
+            [alias.general]
+            "ls" = "ls -l"
+            [alias.group.aws]
+            "aws_help" = "aws --help"
+        "#;
+        let mut mock = MockFileReader::new();
+        let file_path = "toml_config.toml".to_string();
+        mock.expect_read_to_string()
+            .with(eq(file_path.clone()))
+            .times(1)
+            .returning(|_| Ok(content.to_string()));
+
+        load_from_file(&mock, file_path).unwrap()
+    }
+
+    fn tui(saves: usize) -> TuiCommand {
+        let mut mock = MockFileReader::new();
+        mock.expect_read_to_string()
+            .returning(|_| Err(io::Error::from(io::ErrorKind::NotFound)));
+        mock.expect_write()
+            .with(eq("toml_config.toml"), mockall::predicate::always())
+            .times(saves)
+            .returning(|_, _| Ok(()));
+        TuiCommand::new(
+            mock_toml_config(),
+            Box::new(mock),
+            "toml_config.toml".to_string(),
+        )
+    }
+
+    fn press(tui: &mut TuiCommand, keys: &str) {
+        for c in keys.chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                '\t' => KeyCode::Tab,
+                c => KeyCode::Char(c),
+            };
+            tui.handle_key(KeyEvent::from(code)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_add_edit_delete() {
+        let mut tui = tui(3);
+        press(&mut tui, "jall\n");
+        assert_eq!(
+            tui.mode,
+            Mode::Input {
+                prompt: Prompt::AddCommand {
+                    alias: "ll".to_string()
+                },
+                buffer: String::new(),
+            }
+        );
+        press(&mut tui, "ls -al\n");
+        assert_eq!(tui.selected_alias().as_deref(), Some("ll"));
+        assert_eq!(
+            tui.toml_config.find("ll", Some("aws")).unwrap().to_string(),
+            "ls -al"
+        );
+
+        tui.handle_key(KeyEvent::from(KeyCode::Char('e'))).unwrap();
+        tui.handle_key(KeyEvent::from(KeyCode::Backspace)).unwrap();
+        press(&mut tui, "h\n");
+        assert_eq!(
+            tui.toml_config.find("ll", Some("aws")).unwrap().to_string(),
+            "ls -ah"
+        );
+
+        press(&mut tui, "dn");
+        assert!(tui.toml_config.find("ll", Some("aws")).is_some());
+        press(&mut tui, "dy");
+        assert!(tui.toml_config.find("ll", Some("aws")).is_none());
+        press(&mut tui, "q");
+        assert!(tui.quit);
+    }
+
+    #[test]
+    fn test_edit_single_step() {
+        let mut tui = tui(1);
+        tui.toml_config
+            .add(
+                "aws/sync",
+                AliasEntry::Table(Box::new(AliasTable {
+                    steps: vec![Step::Detailed {
+                        run: "aws s3 sync".to_string(),
+                        continue_on_error: true,
+                    }],
+                    ..AliasTable::default()
+                })),
+                None,
+            )
+            .unwrap();
+        press(&mut tui, "j");
+        tui.select_alias("sync");
+        press(&mut tui, "e");
+        assert_eq!(
+            tui.mode,
+            Mode::Input {
+                prompt: Prompt::Edit {
+                    alias: "sync".to_string()
+                },
+                buffer: "aws s3 sync".to_string(),
+            }
+        );
+        press(&mut tui, " .\n");
+        let entry = tui.toml_config.find("sync", Some("aws")).unwrap();
+        assert_eq!(
+            entry.steps(),
+            vec![Step::Detailed {
+                run: "aws s3 sync .".to_string(),
+                continue_on_error: true,
+            }]
+        );
+        assert_eq!(entry.table().unwrap().command, None);
+    }
+
+    #[test]
+    fn test_run_prompts_for_missing_values() {
+        let mut tui = tui(1);
+        press(&mut tui, "aecho\necho {1}\n\n");
+        assert!(matches!(
+            tui.mode,
+            Mode::Input {
+                prompt: Prompt::RunArgs { .. },
+                ..
+            }
+        ));
+        press(&mut tui, "hello\n");
+        assert_eq!(tui.output, vec!["$ echo hello", "hello", "[exit 0]"]);
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = parse_args("us-east-1 task=web");
+        assert_eq!(args.positional, vec!["us-east-1"]);
+        assert_eq!(args.named.get("task").map(String::as_str), Some("web"));
+    }
+
+    #[test]
+    fn test_draw() {
+        let tui = tui(0);
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| tui.draw(frame)).unwrap();
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(content.contains("general"));
+        assert!(content.contains("aws"));
+        assert!(content.contains("ls -l"));
+    }
+}
@@ -1,7 +1,25 @@
-mod config;
 mod command;
+mod config;
+mod document;
+mod entry;
+mod error;
 mod fs;
+mod layers;
+mod output;
+mod picker;
+mod search;
+mod shell;
+mod template;
 
-pub use config::*;
 pub use command::*;
+pub use config::*;
+pub use document::*;
+pub use entry::*;
+pub use error::*;
 pub use fs::*;
+pub use layers::*;
+pub use output::*;
+pub use picker::*;
+pub use search::*;
+pub use shell::*;
+pub use template::*;
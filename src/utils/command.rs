@@ -1,21 +1,199 @@
-use std::process::Command;
+use crate::utils::{Error, Step};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Stdio};
+#[cfg(unix)]
+use std::sync::{Mutex, PoisonError};
 
-pub fn run_command(command: &str) {
-    let output = if cfg!(target_os = "windows") {
-        Command::new("cmd")
-            .args(["/C", command])
-            .output()
-            .expect("failed to execute process")
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunOptions {
+    pub capture: bool,
+    pub env: HashMap<String, String>,
+    pub cwd: Option<String>,
+    pub clear_env: bool,
+}
+
+pub fn expand_home(path: &str) -> PathBuf {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+    match (path.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            let mut expanded = PathBuf::from(home);
+            expanded.push(rest.trim_start_matches(['/', '\\']));
+            expanded
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(target_os = "windows") {
+        let mut process = Command::new("cmd");
+        process.args(["/C", command]);
+        process
     } else {
-        Command::new("sh")
-            .arg("-c")
-            .arg(command)
-            .output()
-            .expect("failed to execute process")
+        let mut process = Command::new("sh");
+        process.arg("-c").arg(command);
+        process
+    }
+}
+
+pub fn exit_code(status: ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    1
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommandOutput {
+    pub code: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+fn prepare_command(command: &str, options: &RunOptions) -> Result<Command, Error> {
+    let mut process = shell_command(command);
+    if options.clear_env {
+        process.env_clear();
+    }
+    process.envs(&options.env);
+    if let Some(cwd) = &options.cwd {
+        let cwd = expand_home(cwd);
+        if !cwd.is_dir() {
+            return Err(Error::Io {
+                path: cwd.display().to_string(),
+                source: io::Error::new(io::ErrorKind::NotFound, "working directory not found"),
+            });
+        }
+        process.current_dir(cwd);
+    }
+    Ok(process)
+}
+
+fn spawn_error(command: &str) -> impl FnOnce(io::Error) -> Error + '_ {
+    move |source| Error::Spawn {
+        command: command.to_string(),
+        source,
+    }
+}
+
+pub fn capture_command(command: &str, options: &RunOptions) -> Result<CommandOutput, Error> {
+    let output = prepare_command(command, options)?
+        .stdin(Stdio::null())
+        .output()
+        .map_err(spawn_error(command))?;
+    Ok(CommandOutput {
+        code: exit_code(output.status),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    })
+}
+
+// Ctrl-C and Ctrl-\ reach the whole foreground process group. While an
+// interactive child runs, the parent ignores them so the child decides what
+// they mean and its exit status is still reported. The child is spawned
+// before the handlers change, so it starts with the default ones. The
+// handlers belong to the whole process, so guards held by several threads
+// share them: the first one in saves the old handlers and the last one out
+// puts them back.
+struct IgnoreInterrupts;
+
+#[cfg(unix)]
+static IGNORING: Mutex<(usize, [libc::sighandler_t; 2])> =
+    Mutex::new((0, [libc::SIG_DFL, libc::SIG_DFL]));
+
+impl IgnoreInterrupts {
+    fn new() -> Self {
+        #[cfg(unix)]
+        {
+            let mut ignoring = IGNORING.lock().unwrap_or_else(PoisonError::into_inner);
+            if ignoring.0 == 0 {
+                ignoring.1 = unsafe {
+                    [
+                        libc::signal(libc::SIGINT, libc::SIG_IGN),
+                        libc::signal(libc::SIGQUIT, libc::SIG_IGN),
+                    ]
+                };
+            }
+            ignoring.0 += 1;
+        }
+        IgnoreInterrupts
+    }
+}
+
+impl Drop for IgnoreInterrupts {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        {
+            let mut ignoring = IGNORING.lock().unwrap_or_else(PoisonError::into_inner);
+            ignoring.0 -= 1;
+            if ignoring.0 == 0 {
+                unsafe {
+                    libc::signal(libc::SIGINT, ignoring.1[0]);
+                    libc::signal(libc::SIGQUIT, ignoring.1[1]);
+                }
+            }
+        }
+    }
+}
+
+pub fn run_command(command: &str, options: &RunOptions) -> Result<i32, Error> {
+    let mut process = prepare_command(command, options)?;
+    let status = if options.capture {
+        let output = process.output().map_err(spawn_error(command))?;
+        println!("status: {}", output.status);
+        println!("stdout: {}", String::from_utf8_lossy(&output.stdout));
+        println!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+        output.status
+    } else {
+        let mut child = process
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(spawn_error(command))?;
+        let _ignore = IgnoreInterrupts::new();
+        child.wait().map_err(spawn_error(command))?
     };
-    println!("status: {}", output.status);
-    println!("stdout: {}", String::from_utf8_lossy(&output.stdout));
-    println!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+    Ok(exit_code(status))
+}
+
+pub fn run_steps(steps: &[Step], options: &RunOptions) -> Result<i32, Error> {
+    let mut outcomes: Vec<Option<i32>> = vec![None; steps.len()];
+    let mut code = 0;
+    for (index, step) in steps.iter().enumerate() {
+        let step_code = run_command(step.run(), options)?;
+        outcomes[index] = Some(step_code);
+        if step_code != 0 && !step.continue_on_error() {
+            code = step_code;
+            break;
+        }
+    }
+    eprintln!("Step summary:");
+    for (index, (step, outcome)) in steps.iter().zip(outcomes).enumerate() {
+        let status = match outcome {
+            Some(0) => "ok".to_string(),
+            Some(code) if step.continue_on_error() => format!("failed ({}, ignored)", code),
+            Some(code) => format!("failed ({})", code),
+            None => "skipped".to_string(),
+        };
+        eprintln!(
+            "  [{}/{}] {:<20} {}",
+            index + 1,
+            steps.len(),
+            status,
+            step.run()
+        );
+    }
+    Ok(code)
 }
 
 #[cfg(test)]
@@ -26,7 +204,7 @@ mod tests {
     fn run_command_on_windows() {
         if cfg!(target_os = "windows") {
             let command = "echo Hello, world!";
-            run_command(command);
+            run_command(command, &RunOptions::default()).unwrap();
         }
     }
 
@@ -34,13 +212,153 @@ mod tests {
     fn run_command_on_unix() {
         if !cfg!(target_os = "windows") {
             let command = "echo Hello, world!";
-            run_command(command);
+            run_command(command, &RunOptions::default()).unwrap();
         }
     }
 
     #[test]
     fn run_command_with_error() {
         let command = "invalid_command";
-        run_command(command);
+        assert_ne!(run_command(command, &RunOptions::default()).unwrap(), 0);
+    }
+
+    #[test]
+    fn run_command_returns_exit_code() {
+        if !cfg!(target_os = "windows") {
+            assert_eq!(run_command("exit 3", &RunOptions::default()).unwrap(), 3);
+        }
+    }
+
+    #[test]
+    fn run_command_maps_signal_to_exit_code() {
+        if !cfg!(target_os = "windows") {
+            let code = run_command("kill -TERM $$", &RunOptions::default()).unwrap();
+            assert_eq!(code, 128 + 15);
+        }
+    }
+
+    // The alias interrupts the process running it, so the check runs in a
+    // test binary of its own rather than next to the other tests.
+    #[test]
+    fn run_command_outlives_interrupt() {
+        if cfg!(target_os = "windows") {
+            return;
+        }
+        if std::env::var_os("RUST_CLI_INTERRUPT_TEST").is_some() {
+            let command =
+                "trap 'echo interrupted' INT; sleep 0.1; kill -INT $PPID; sleep 0.1; exit 5";
+            assert_eq!(run_command(command, &RunOptions::default()).unwrap(), 5);
+            return;
+        }
+        let output = Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "utils::command::tests::run_command_outlives_interrupt",
+            ])
+            .env("RUST_CLI_INTERRUPT_TEST", "1")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("1 passed"));
+    }
+
+    #[test]
+    fn ignore_interrupts_nests() {
+        let outer = IgnoreInterrupts::new();
+        let inner = IgnoreInterrupts::new();
+        drop(outer);
+        #[cfg(unix)]
+        assert_eq!(
+            unsafe { libc::signal(libc::SIGINT, libc::SIG_IGN) },
+            libc::SIG_IGN
+        );
+        drop(inner);
+    }
+
+    #[test]
+    fn run_command_with_capture() {
+        let command = "echo Hello, world!";
+        let options = RunOptions {
+            capture: true,
+            ..RunOptions::default()
+        };
+        run_command(command, &options).unwrap();
+    }
+
+    #[test]
+    fn capture_command_returns_output() {
+        if !cfg!(target_os = "windows") {
+            let output =
+                capture_command("echo out; echo err >&2; exit 4", &RunOptions::default()).unwrap();
+            assert_eq!(
+                output,
+                CommandOutput {
+                    code: 4,
+                    stdout: "out\n".to_string(),
+                    stderr: "err\n".to_string(),
+                }
+            );
+        }
+    }
+
+    fn step(run: &str, continue_on_error: bool) -> Step {
+        Step::Detailed {
+            run: run.to_string(),
+            continue_on_error,
+        }
+    }
+
+    #[test]
+    fn run_steps_stops_on_failure() {
+        if !cfg!(target_os = "windows") {
+            let steps = vec![
+                step("exit 0", false),
+                step("exit 4", false),
+                step("exit 5", false),
+            ];
+            assert_eq!(run_steps(&steps, &RunOptions::default()).unwrap(), 4);
+        }
+    }
+
+    #[test]
+    fn run_steps_continues_on_error() {
+        if !cfg!(target_os = "windows") {
+            let steps = vec![step("exit 4", true), step("exit 0", false)];
+            assert_eq!(run_steps(&steps, &RunOptions::default()).unwrap(), 0);
+        }
+    }
+
+    #[test]
+    fn run_command_with_env_and_cwd() {
+        if !cfg!(target_os = "windows") {
+            let options = RunOptions {
+                env: HashMap::from([("RUST_CLI_TEST".to_string(), "1".to_string())]),
+                cwd: Some("/".to_string()),
+                clear_env: true,
+                ..RunOptions::default()
+            };
+            let command = "test \"$RUST_CLI_TEST\" = 1 && test \"$(pwd)\" = / && test -z \"$HOME\"";
+            assert_eq!(run_command(command, &options).unwrap(), 0);
+        }
+    }
+
+    #[test]
+    fn run_command_with_missing_cwd() {
+        let options = RunOptions {
+            cwd: Some("/path/that/does/not/exist".to_string()),
+            ..RunOptions::default()
+        };
+        assert!(matches!(
+            run_command("true", &options),
+            Err(Error::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn expand_home_with_tilde() {
+        if let Some(home) = std::env::var_os("HOME") {
+            assert_eq!(expand_home("~/src"), PathBuf::from(home).join("src"));
+        }
+        assert_eq!(expand_home("/tmp"), PathBuf::from("/tmp"));
     }
 }
@@ -1,55 +1,227 @@
-use crate::utils::{run_command, FileReader};
+use crate::utils::{
+    capture_command, escape_template, expand_all, expand_home, is_shell_name, pick,
+    preserve_format, render_record, render_records, render_table, run_command, run_steps,
+    serialize_output, sorted, state_path, suggest, AliasEntry, AliasRecord, AliasTable,
+    CommandOutput, Error, FileReader, GroupRecord, MatchMode, Matcher, OutputFormat, PickerItem,
+    RunOptions, Scope, SearchHit, ShellKind, Step, Template, TemplateArgs,
+};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+pub const CONFIG_ENV: &str = "RUST_CLI_CONFIG";
+const MAX_ALIAS_DEPTH: usize = 16;
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
 struct AliasConfig {
-    general: HashMap<String, String>,
-    group: Option<HashMap<String, HashMap<String, String>>>,
+    #[serde(default, serialize_with = "sorted")]
+    general: HashMap<String, AliasEntry>,
+    #[serde(default, serialize_with = "sorted_groups")]
+    group: Option<HashMap<String, HashMap<String, AliasEntry>>>,
+}
+
+fn sorted_groups<S: serde::Serializer>(
+    groups: &Option<HashMap<String, HashMap<String, AliasEntry>>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let groups = groups.as_ref().map(|groups| {
+        groups
+            .iter()
+            .map(|(name, aliases)| (name, aliases.iter().collect::<BTreeMap<_, _>>()))
+            .collect::<BTreeMap<_, _>>()
+    });
+    groups.serialize(serializer)
+}
+
+fn resolve_config_path(
+    flag: Option<&str>,
+    env: Option<String>,
+    xdg_config_home: Option<String>,
+) -> PathBuf {
+    if let Some(path) = flag
+        .map(str::to_string)
+        .or(env.filter(|path| !path.is_empty()))
+    {
+        return expand_home(&path);
+    }
+    let config_home = xdg_config_home
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .unwrap_or_else(|| expand_home("~/.config"));
+    config_home.join(env!("CARGO_PKG_NAME")).join("config.toml")
+}
+
+// The config file in use: `--config`, then `$RUST_CLI_CONFIG`, then
+// `$XDG_CONFIG_HOME/rust_cli/config.toml` with `~/.config` as the fallback.
+pub fn config_path(flag: Option<&str>) -> String {
+    resolve_config_path(
+        flag,
+        std::env::var(CONFIG_ENV).ok(),
+        std::env::var("XDG_CONFIG_HOME").ok(),
+    )
+    .display()
+    .to_string()
 }
 
 pub fn load_from_file(
     file_reader: &dyn FileReader,
     file_path: String,
-) -> Result<TomlConfig, Box<dyn std::error::Error>> {
-    let content = file_reader.read_to_string(&file_path)?;
-    let toml_config: TomlConfig = toml::from_str(&content)?;
+) -> Result<TomlConfig, Error> {
+    let content = file_reader
+        .read_to_string(&file_path)
+        .map_err(|source| Error::Io {
+            path: file_path.clone(),
+            source,
+        })?;
+    let toml_config: TomlConfig = toml::from_str(&content).map_err(|source| Error::Parse {
+        path: file_path.clone(),
+        source,
+    })?;
     Ok(toml_config)
 }
 
+// A missing config reads as an empty one, which is written out on the first
+// save.
+pub fn load_or_default(
+    file_reader: &dyn FileReader,
+    file_path: String,
+) -> Result<TomlConfig, Error> {
+    match load_from_file(file_reader, file_path) {
+        Err(Error::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => {
+            Ok(TomlConfig::default())
+        }
+        result => result,
+    }
+}
+
 pub fn save_to_file(
     file_reader: &dyn FileReader,
     file_path: String,
     toml_config: &TomlConfig,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), Error> {
     let content = toml::to_string(toml_config)?;
-    file_reader.write(&file_path, &content)?;
+    let content = match file_reader.read_to_string(&file_path) {
+        Ok(previous) => {
+            let content = preserve_format(&previous, &content);
+            if content == previous {
+                return Ok(());
+            }
+            let backups = toml_config.settings.backups();
+            rotate_backups(file_reader, &file_path, &previous, backups)?;
+            content
+        }
+        Err(source) if source.kind() == io::ErrorKind::NotFound => content,
+        Err(source) => {
+            return Err(Error::Io {
+                path: file_path,
+                source,
+            })
+        }
+    };
+    file_reader
+        .write(&file_path, &content)
+        .map_err(|source| Error::Io {
+            path: file_path.clone(),
+            source,
+        })?;
     Ok(())
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+pub fn backup_path(file_path: &str, index: usize) -> String {
+    format!("{}.bak.{}", state_path(file_path), index)
+}
+
+// Shifts `.bak.1` to `.bak.2` and so on, dropping the oldest, then stores
+// `previous` as `.bak.1`.
+fn rotate_backups(
+    file_reader: &dyn FileReader,
+    file_path: &str,
+    previous: &str,
+    keep: usize,
+) -> Result<(), Error> {
+    if keep == 0 {
+        return Ok(());
+    }
+    for index in (1..keep).rev() {
+        let from = backup_path(file_path, index);
+        match file_reader.rename(&from, &backup_path(file_path, index + 1)) {
+            Err(source) if source.kind() != io::ErrorKind::NotFound => {
+                return Err(Error::Io { path: from, source })
+            }
+            _ => {}
+        }
+    }
+    let path = backup_path(file_path, 1);
+    file_reader
+        .write(&path, previous)
+        .map_err(|source| Error::Io { path, source })
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum OnConflict {
+    #[default]
+    Skip,
+    Overwrite,
+    Rename,
+    Abort,
+}
+
+const DEFAULT_BACKUPS: usize = 3;
+
+// Settings left out of a file are `None`, so a layer only overrides the
+// settings it names.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Settings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_run_suggestion: Option<bool>,
+    // How many previous versions of the config to keep as `.bak.N` files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backups: Option<usize>,
+}
+
+impl Settings {
+    fn is_default(&self) -> bool {
+        *self == Settings::default()
+    }
+
+    pub fn auto_run_suggestion(&self) -> bool {
+        self.auto_run_suggestion.unwrap_or(false)
+    }
+
+    pub fn backups(&self) -> usize {
+        self.backups.unwrap_or(DEFAULT_BACKUPS)
+    }
+
+    fn merge(&mut self, layer: Settings) {
+        self.auto_run_suggestion = layer.auto_run_suggestion.or(self.auto_run_suggestion);
+        self.backups = layer.backups.or(self.backups);
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct TomlConfig {
+    #[serde(default, skip_serializing_if = "Settings::is_default")]
+    pub settings: Settings,
+    #[serde(default)]
     alias: AliasConfig,
+    #[serde(skip)]
+    origins: HashMap<String, Scope>,
 }
 
 #[cfg(test)]
 impl TomlConfig {
     pub(crate) fn contains(&mut self, alias: &str, group_name: Option<&str>) -> bool {
         match self.get_group(group_name) {
-            Some(alias_map) => {
-                if alias_map.contains_key(alias) {
-                    true
-                } else {
-                    false
-                }
-            }
-            None => false
+            Some(alias_map) => alias_map.contains_key(alias),
+            None => false,
         }
     }
 }
 
 impl TomlConfig {
-    fn get_group(&mut self, group_name: Option<&str>) -> Option<&mut HashMap<String, String>> {
+    fn get_group(&mut self, group_name: Option<&str>) -> Option<&mut HashMap<String, AliasEntry>> {
         match group_name {
             Some(group) => {
                 if let Some(group_map) = self.alias.group.as_mut() {
@@ -62,77 +234,896 @@ impl TomlConfig {
         }
     }
 
-    pub fn add(&mut self, alias: &str, command: &str, group_name: Option<&str>) {
-        let alias_map = match group_name {
+    fn require_group(
+        &mut self,
+        group_name: Option<&str>,
+    ) -> Result<&mut HashMap<String, AliasEntry>, Error> {
+        if self.get_group(group_name).is_none() {
+            let group = group_name.unwrap_or_default();
+            return Err(Error::GroupNotFound {
+                group: group.to_string(),
+                suggestions: self.group_suggestions(group),
+            });
+        }
+        Ok(self.get_group(group_name).unwrap())
+    }
+
+    fn require_alias(
+        &mut self,
+        alias: &str,
+        group_name: Option<&str>,
+    ) -> Result<AliasEntry, Error> {
+        if let Some(entry) = self.require_group(group_name)?.get(alias) {
+            return Ok(entry.clone());
+        }
+        Err(self.alias_not_found(alias, group_name))
+    }
+
+    fn alias_not_found(&self, alias: &str, group_name: Option<&str>) -> Error {
+        Error::AliasNotFound {
+            alias: alias.to_string(),
+            group: group_name.map(str::to_string),
+            suggestions: self
+                .alias_suggestions(alias)
+                .into_iter()
+                .map(|(_, address)| address)
+                .collect(),
+        }
+    }
+
+    fn alias_suggestions(&self, alias: &str) -> Vec<(usize, String)> {
+        suggest(
+            alias,
+            self.entries()
+                .into_iter()
+                .map(|(group, name, _)| (name, Self::address(name, group))),
+        )
+    }
+
+    fn group_suggestions(&self, group: &str) -> Vec<String> {
+        suggest(
+            group,
+            self.alias
+                .group
+                .iter()
+                .flatten()
+                .map(|(name, _)| (name.as_str(), name.clone())),
+        )
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect()
+    }
+
+    // With `auto_run_suggestion` set, a missing alias runs in place of the
+    // only suggestion one edit away. A group given by the user limits the
+    // suggestions to that group.
+    fn auto_run_target(
+        &self,
+        alias: &str,
+        group_name: Option<&str>,
+    ) -> Option<(String, Option<String>)> {
+        if !self.settings.auto_run_suggestion() || self.find(alias, group_name).is_some() {
+            return None;
+        }
+        let close: Vec<String> = self
+            .alias_suggestions(alias)
+            .into_iter()
+            .filter(|(distance, address)| {
+                *distance <= 1
+                    && (group_name.is_none() || Self::split_address(address, None).1 == group_name)
+            })
+            .map(|(_, address)| address)
+            .collect();
+        let [address] = close.as_slice() else {
+            return None;
+        };
+        eprintln!(
+            "note: alias '{}' not found, running '{}'",
+            Self::address(alias, group_name),
+            address
+        );
+        let (alias, group_name) = Self::split_address(address, None);
+        Some((alias.to_string(), group_name.map(str::to_string)))
+    }
+
+    // Accepts `group/alias` wherever an alias name is expected; a group given
+    // in the address takes precedence over `--group`.
+    fn split_address<'a>(
+        alias: &'a str,
+        group_name: Option<&'a str>,
+    ) -> (&'a str, Option<&'a str>) {
+        match alias.split_once('/') {
+            Some((group, alias)) => (alias, Some(group)),
+            None => (alias, group_name),
+        }
+    }
+
+    // `/` separates a group from an alias in an address, so neither may
+    // contain one.
+    fn check_name(name: &str) -> Result<(), Error> {
+        if name.is_empty() || name.contains('/') {
+            return Err(Error::InvalidName(name.to_string()));
+        }
+        Ok(())
+    }
+
+    fn check_names(alias: &str, group_name: Option<&str>) -> Result<(), Error> {
+        Self::check_name(alias)?;
+        group_name.map_or(Ok(()), Self::check_name)
+    }
+
+    // Without a group, an alias missing from the general aliases is looked up
+    // in every group and must be unique there.
+    fn resolve(
+        &self,
+        alias: &str,
+        group_name: Option<&str>,
+    ) -> Result<(String, Option<String>), Error> {
+        let (alias, group_name) = Self::split_address(alias, group_name);
+        if group_name.is_some() || self.alias.general.contains_key(alias) {
+            return Ok((alias.to_string(), group_name.map(str::to_string)));
+        }
+        let mut groups: Vec<&String> = self
+            .alias
+            .group
+            .iter()
+            .flatten()
+            .filter(|(_, alias_map)| alias_map.contains_key(alias))
+            .map(|(group, _)| group)
+            .collect();
+        groups.sort();
+        match groups.as_slice() {
+            [] => Ok((alias.to_string(), None)),
+            [group] => Ok((alias.to_string(), Some(group.to_string()))),
+            _ => Err(Error::AmbiguousAlias {
+                alias: alias.to_string(),
+                candidates: groups
+                    .iter()
+                    .map(|group| Self::address(alias, Some(group)))
+                    .collect(),
+            }),
+        }
+    }
+
+    pub fn entries(&self) -> Vec<(Option<&str>, &str, &AliasEntry)> {
+        let mut entries: Vec<(Option<&str>, &str, &AliasEntry)> = self
+            .alias
+            .general
+            .iter()
+            .map(|(alias, entry)| (None, alias.as_str(), entry))
+            .collect();
+        for (group, alias_map) in self.alias.group.iter().flatten() {
+            for (alias, entry) in alias_map {
+                entries.push((Some(group.as_str()), alias.as_str(), entry));
+            }
+        }
+        entries.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+        entries
+    }
+
+    fn address(alias: &str, group_name: Option<&str>) -> String {
+        match group_name {
+            Some(group) => format!("{}/{}", group, alias),
+            None => alias.to_string(),
+        }
+    }
+
+    // Replaces `@alias` and `@group/alias` words with the referenced command.
+    // An unqualified reference is looked up in the referencing alias's group
+    // before the general aliases; `@@` escapes a literal `@`. Words that name
+    // no alias, such as `@payload.json` or `@angular/cli`, are left as they
+    // are, and so is anything inside single quotes.
+    // Doubles each `@` that `expand_references` would read as the start of a
+    // reference, so the command keeps its `@` words as written.
+    fn escape_references(command: &str) -> String {
+        let mut escaped = String::with_capacity(command.len());
+        let mut quote: Option<char> = None;
+        let mut chars = command.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' if quote != Some('\'') => {
+                    escaped.push(c);
+                    escaped.extend(chars.next());
+                    continue;
+                }
+                '\'' | '"' if quote.is_none() => quote = Some(c),
+                _ if quote == Some(c) => quote = None,
+                _ => {}
+            }
+            let at_word_start = escaped
+                .chars()
+                .last()
+                .is_none_or(|prev| prev.is_whitespace() || ";&|(".contains(prev));
+            if c == '@' && at_word_start && quote != Some('\'') {
+                escaped.push('@');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    fn expand_references(
+        &self,
+        command: &str,
+        group_name: Option<&str>,
+        stack: &mut Vec<String>,
+    ) -> Result<String, Error> {
+        let mut output = String::new();
+        let mut quote: Option<char> = None;
+        let mut rest = command;
+        while let Some(c) = rest.chars().next() {
+            match c {
+                '\\' if quote != Some('\'') => {
+                    let len = rest.chars().take(2).map(char::len_utf8).sum();
+                    output.push_str(&rest[..len]);
+                    rest = &rest[len..];
+                    continue;
+                }
+                '\'' | '"' if quote.is_none() => quote = Some(c),
+                _ if quote == Some(c) => quote = None,
+                _ => {}
+            }
+            let at_word_start = output
+                .chars()
+                .last()
+                .is_none_or(|prev| prev.is_whitespace() || ";&|(".contains(prev));
+            if c != '@' || !at_word_start || quote == Some('\'') {
+                output.push(c);
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+            if rest.starts_with("@@") {
+                output.push('@');
+                rest = &rest[2..];
+                continue;
+            }
+            let len = rest[1..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || "_-./".contains(c)))
+                .unwrap_or(rest.len() - 1);
+            let reference = &rest[1..1 + len];
+            if reference.is_empty() {
+                output.push(c);
+                rest = &rest[1..];
+                continue;
+            }
+            let (target_group, target) = match reference.split_once('/') {
+                Some((group, alias)) => (Some(group), alias),
+                None => match group_name {
+                    Some(group) if self.find(reference, Some(group)).is_some() => {
+                        (Some(group), reference)
+                    }
+                    _ => (None, reference),
+                },
+            };
+            let Some(entry) = self.find(target, target_group) else {
+                output.push_str(&rest[..1 + len]);
+                rest = &rest[1 + len..];
+                continue;
+            };
+            let address = Self::address(target, target_group);
+            if stack.contains(&address) {
+                stack.push(address);
+                return Err(Error::AliasCycle(stack.clone()));
+            }
+            if stack.len() >= MAX_ALIAS_DEPTH {
+                return Err(Error::AliasDepthExceeded(MAX_ALIAS_DEPTH));
+            }
+            let referenced = match entry.steps().as_slice() {
+                [step] => step.run().to_string(),
+                _ => return Err(Error::MultiStepReference(address)),
+            };
+            stack.push(address);
+            output.push_str(&self.expand_references(&referenced, target_group, stack)?);
+            stack.pop();
+            rest = &rest[1 + len..];
+        }
+        Ok(output)
+    }
+
+    fn get_or_create_group(
+        &mut self,
+        group_name: Option<&str>,
+    ) -> &mut HashMap<String, AliasEntry> {
+        match group_name {
             Some(group) => {
                 let group_map = self.alias.group.get_or_insert_with(HashMap::new);
-                group_map
-                    .entry(group.to_string())
-                    .or_insert_with(HashMap::new)
+                group_map.entry(group.to_string()).or_default()
             }
             None => &mut self.alias.general,
-        };
+        }
+    }
+
+    // Lays `layer` over this config, remembering where each alias came from.
+    pub fn merge(&mut self, layer: TomlConfig, scope: Scope) {
+        self.settings.merge(layer.settings);
+        for (alias, entry) in layer.alias.general {
+            self.origins.insert(Self::address(&alias, None), scope);
+            self.alias.general.insert(alias, entry);
+        }
+        for (group, alias_map) in layer.alias.group.into_iter().flatten() {
+            self.get_or_create_group(Some(&group));
+            for (alias, entry) in alias_map {
+                self.origins
+                    .insert(Self::address(&alias, Some(&group)), scope);
+                self.get_or_create_group(Some(&group)).insert(alias, entry);
+            }
+        }
+    }
+
+    pub fn origin(&self, alias: &str, group_name: Option<&str>) -> Option<Scope> {
+        self.origins.get(&Self::address(alias, group_name)).copied()
+    }
+
+    pub fn add(
+        &mut self,
+        alias: &str,
+        entry: impl Into<AliasEntry>,
+        group_name: Option<&str>,
+    ) -> Result<(), Error> {
+        let (alias, group_name) = Self::split_address(alias, group_name);
+        Self::check_names(alias, group_name)?;
+        let alias_map = self.get_or_create_group(group_name);
+        let mut entry = entry.into();
+        entry.touch(alias_map.get(alias));
+        alias_map.insert(alias.to_string(), entry);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, alias: &str, group_name: Option<&str>) -> Result<(), Error> {
+        let (alias, group_name) = self.resolve(alias, group_name)?;
+        let (alias, group_name) = (alias.as_str(), group_name.as_deref());
+        self.require_alias(alias, group_name)?;
+        self.require_group(group_name)?.remove(alias);
+        if let Some(group) = group_name {
+            self.prune_group(group);
+        }
+        Ok(())
+    }
 
-        if alias_map.contains_key(alias) {
-            alias_map.get_mut(alias).map(|c| *c = command.to_string());
+    fn transfer(
+        &mut self,
+        from: (&str, Option<&str>),
+        to: (&str, Option<&str>),
+        overwrite: bool,
+        keep_source: bool,
+    ) -> Result<(), Error> {
+        let (alias, group_name) = from;
+        let (new_alias, new_group) = to;
+        let mut entry = self.require_alias(alias, group_name)?;
+        Self::check_names(new_alias, new_group)?;
+        if from == to {
+            return Err(Error::AliasExists {
+                alias: new_alias.to_string(),
+                group: new_group.map(str::to_string),
+            });
+        }
+        if self.find(new_alias, new_group).is_some() && !overwrite {
+            return Err(Error::AliasExists {
+                alias: new_alias.to_string(),
+                group: new_group.map(str::to_string),
+            });
+        }
+        if keep_source {
+            if let AliasEntry::Table(table) = &mut entry {
+                table.created_at = None;
+            }
+            entry.touch(None);
         } else {
-            alias_map.insert(alias.to_string(), command.to_string());
+            self.remove(alias, group_name)?;
         }
+        self.get_or_create_group(new_group)
+            .insert(new_alias.to_string(), entry);
+        Ok(())
     }
 
-    pub fn remove(&mut self, alias: &str, group_name: Option<&str>) {
-        match self.get_group(group_name) {
-            Some(alias_map) => {
-                if alias_map.contains_key(alias) {
-                    alias_map.remove(alias);
-                } else {
-                    println!("Alias not found");
+    pub fn rename(
+        &mut self,
+        alias: &str,
+        group_name: Option<&str>,
+        new_alias: &str,
+        overwrite: bool,
+    ) -> Result<(), Error> {
+        let (alias, group_name) = self.resolve(alias, group_name)?;
+        let (alias, group_name) = (alias.as_str(), group_name.as_deref());
+        self.transfer(
+            (alias, group_name),
+            (new_alias, group_name),
+            overwrite,
+            false,
+        )
+    }
+
+    pub fn move_to(
+        &mut self,
+        alias: &str,
+        group_name: Option<&str>,
+        new_group: Option<&str>,
+        overwrite: bool,
+    ) -> Result<(), Error> {
+        let (alias, group_name) = self.resolve(alias, group_name)?;
+        let (alias, group_name) = (alias.as_str(), group_name.as_deref());
+        self.transfer((alias, group_name), (alias, new_group), overwrite, false)
+    }
+
+    pub fn copy(
+        &mut self,
+        from: (&str, Option<&str>),
+        to: (&str, Option<&str>),
+        overwrite: bool,
+    ) -> Result<(), Error> {
+        let (alias, group_name) = self.resolve(from.0, from.1)?;
+        let to = Self::split_address(to.0, to.1);
+        self.transfer((&alias, group_name.as_deref()), to, overwrite, true)
+    }
+
+    fn prune_group(&mut self, group_name: &str) {
+        if let Some(group_map) = self.alias.group.as_mut() {
+            if group_map.get(group_name).is_some_and(HashMap::is_empty) {
+                group_map.remove(group_name);
+            }
+            if group_map.is_empty() {
+                self.alias.group = None;
+            }
+        }
+    }
+
+    pub fn list_groups(&self, output: OutputFormat) -> Result<(), Error> {
+        let mut groups: Vec<GroupRecord> = self
+            .alias
+            .group
+            .iter()
+            .flatten()
+            .map(|(name, aliases)| GroupRecord {
+                group: name.clone(),
+                aliases: aliases.len(),
+            })
+            .collect();
+        groups.sort_by(|a, b| a.group.cmp(&b.group));
+        match output {
+            OutputFormat::Plain => {
+                if groups.is_empty() {
+                    println!("No groups found");
+                }
+                for group in groups {
+                    println!("{} ({} aliases)", group.group, group.aliases);
                 }
             }
-            None => println!("Group not found"),
+            OutputFormat::Table => {
+                let rows: Vec<Vec<String>> = groups
+                    .into_iter()
+                    .map(|group| vec![group.group, group.aliases.to_string()])
+                    .collect();
+                print!("{}", render_table(&["GROUP", "ALIASES"], &rows));
+            }
+            OutputFormat::Json | OutputFormat::Yaml => {
+                print!("{}", serialize_output(&groups, output)?);
+            }
         }
+        Ok(())
     }
 
-    pub fn list(&mut self, group_name: Option<&str>) {
-        match self.get_group(group_name) {
-            Some(alias_map) => {
-                if alias_map.is_empty() {
-                    println!("No aliases found");
-                } else {
-                    for (alias, command) in alias_map.iter() {
-                        println!("{}: {}", alias, command);
+    pub fn create_group(&mut self, group_name: &str) -> Result<(), Error> {
+        Self::check_name(group_name)?;
+        let group_map = self.alias.group.get_or_insert_with(HashMap::new);
+        if group_map.contains_key(group_name) {
+            return Err(Error::GroupExists(group_name.to_string()));
+        }
+        group_map.insert(group_name.to_string(), HashMap::new());
+        Ok(())
+    }
+
+    pub fn delete_group(&mut self, group_name: &str, force: bool) -> Result<(), Error> {
+        let aliases = self.require_group(Some(group_name))?.len();
+        if aliases > 0 && !force {
+            return Err(Error::GroupNotEmpty {
+                group: group_name.to_string(),
+                aliases,
+            });
+        }
+        if let Some(group_map) = self.alias.group.as_mut() {
+            group_map.remove(group_name);
+            if group_map.is_empty() {
+                self.alias.group = None;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn rename_group(&mut self, group_name: &str, new_name: &str) -> Result<(), Error> {
+        self.require_group(Some(group_name))?;
+        Self::check_name(new_name)?;
+        let group_map = self.alias.group.get_or_insert_with(HashMap::new);
+        if group_map.contains_key(new_name) {
+            return Err(Error::GroupExists(new_name.to_string()));
+        }
+        if let Some(aliases) = group_map.remove(group_name) {
+            group_map.insert(new_name.to_string(), aliases);
+        }
+        Ok(())
+    }
+
+    fn record(&self, alias: &str, group_name: Option<&str>, entry: &AliasEntry) -> AliasRecord {
+        AliasRecord::new(group_name, alias, entry, self.origin(alias, group_name))
+    }
+
+    pub fn list(&mut self, group_name: Option<&str>, output: OutputFormat) -> Result<(), Error> {
+        let alias_map = self.require_group(group_name)?.clone();
+        let mut records: Vec<AliasRecord> = alias_map
+            .iter()
+            .map(|(alias, entry)| self.record(alias, group_name, entry))
+            .collect();
+        records.sort_by(|a, b| a.alias.cmp(&b.alias));
+        if records.is_empty() && output == OutputFormat::Plain {
+            println!("No aliases found");
+        }
+        print!("{}", render_records(&records, output)?);
+        Ok(())
+    }
+
+    pub fn search_hits(&self, matcher: &Matcher, mode: MatchMode) -> Vec<SearchHit> {
+        let mut hits: Vec<SearchHit> = self
+            .entries()
+            .into_iter()
+            .filter_map(|(group, alias, entry)| {
+                let command = entry.to_string();
+                let alias_match = matcher.find(alias);
+                let command_match = matcher.find(&command);
+                let tag_matches: Vec<_> =
+                    entry.tags().iter().map(|tag| matcher.find(tag)).collect();
+                let score = [
+                    alias_match.as_ref().map(|m| m.score + 10),
+                    command_match.as_ref().map(|m| m.score),
+                ]
+                .into_iter()
+                .chain(tag_matches.iter().map(|m| m.as_ref().map(|m| m.score)))
+                .flatten()
+                .max()?;
+                Some(SearchHit {
+                    group: group.map(str::to_string),
+                    alias: alias.to_string(),
+                    command,
+                    tags: entry.tags().to_vec(),
+                    score,
+                    alias_match,
+                    command_match,
+                    tag_matches,
+                })
+            })
+            .collect();
+        if mode == MatchMode::Fuzzy {
+            hits.sort_by_key(|hit| std::cmp::Reverse(hit.score));
+        }
+        hits
+    }
+
+    pub fn search(
+        &self,
+        pattern: &str,
+        mode: MatchMode,
+        output: OutputFormat,
+    ) -> Result<(), Error> {
+        let matcher = Matcher::new(pattern, mode)?;
+        let hits = self.search_hits(&matcher, mode);
+        if output != OutputFormat::Plain {
+            let records: Vec<AliasRecord> = hits
+                .iter()
+                .filter_map(|hit| {
+                    let group = hit.group.as_deref();
+                    Some(self.record(&hit.alias, group, self.find(&hit.alias, group)?))
+                })
+                .collect();
+            print!("{}", render_records(&records, output)?);
+            return Ok(());
+        }
+        if hits.is_empty() {
+            println!("No aliases found");
+        }
+        let color = std::io::stdout().is_terminal();
+        for hit in hits {
+            println!("{}", hit.render(color));
+        }
+        Ok(())
+    }
+
+    pub fn pick(&self, group_name: Option<&str>) -> Result<Option<String>, Error> {
+        let entries: Vec<_> = self
+            .entries()
+            .into_iter()
+            .filter(|(group, _, _)| group_name.is_none() || *group == group_name)
+            .collect();
+        if let Some(group) = group_name {
+            if entries.is_empty() {
+                return Err(Error::GroupNotFound {
+                    group: group.to_string(),
+                    suggestions: self.group_suggestions(group),
+                });
+            }
+        }
+        let items: Vec<PickerItem> = entries
+            .iter()
+            .map(|(group, alias, entry)| PickerItem {
+                label: Self::address(alias, *group),
+                preview: entry.to_string(),
+            })
+            .collect();
+        let picked = pick(&items).map_err(Error::Terminal)?;
+        Ok(picked.map(|index| items[index].label.clone()))
+    }
+
+    // The command a simple alias stands for, or `None` when it needs
+    // `alias exec` for steps, placeholders or its own environment.
+    fn inline_command(
+        &self,
+        alias: &str,
+        group_name: Option<&str>,
+        entry: &AliasEntry,
+    ) -> Option<String> {
+        let [step] = entry.steps().try_into().ok()?;
+        if entry
+            .table()
+            .is_some_and(|table| !table.env.is_empty() || table.cwd.is_some() || table.clear_env)
+        {
+            return None;
+        }
+        let mut stack = vec![Self::address(alias, group_name)];
+        let command = self
+            .expand_references(step.run(), group_name, &mut stack)
+            .ok()?;
+        let template = Template::parse(&command);
+        if template.has_placeholders() {
+            return None;
+        }
+        template.render(&TemplateArgs::default()).ok()
+    }
+
+    fn has_placeholders(&self, alias: &str, group_name: Option<&str>, entry: &AliasEntry) -> bool {
+        let mut stack = vec![Self::address(alias, group_name)];
+        entry.steps().iter().any(|step| {
+            let command = self
+                .expand_references(step.run(), group_name, &mut stack)
+                .unwrap_or_else(|_| step.run().to_string());
+            Template::parse(&command).has_placeholders()
+        })
+    }
+
+    pub fn export(
+        &self,
+        shell: ShellKind,
+        group_name: Option<&str>,
+        prefix: bool,
+    ) -> Result<String, Error> {
+        if let Some(group) = group_name {
+            if !self.group_names().iter().any(|name| name == group) {
+                return Err(Error::GroupNotFound {
+                    group: group.to_string(),
+                    suggestions: self.group_suggestions(group),
+                });
+            }
+        }
+        let program = env!("CARGO_PKG_NAME");
+        let mut names: Vec<String> = Vec::new();
+        let mut lines: Vec<String> = Vec::new();
+        for (group, alias, entry) in self.entries() {
+            if group_name.is_some() && group != group_name {
+                continue;
+            }
+            let name = match group {
+                Some(group) if prefix => format!("{}_{}", group, alias),
+                _ => alias.to_string(),
+            };
+            let address = Self::address(alias, group);
+            if !is_shell_name(&name) {
+                lines.push(format!("# skipped {}: not a valid shell name", address));
+                continue;
+            }
+            if names.contains(&name) {
+                lines.push(format!(
+                    "# skipped {}: '{}' is already defined",
+                    address, name
+                ));
+                continue;
+            }
+            let definition = match self.inline_command(alias, group, entry) {
+                Some(command) => shell.alias(&name, &command),
+                None => {
+                    let mut args = vec!["alias", "exec", "-a", address.as_str()];
+                    // Arguments go straight to the command, flags included,
+                    // unless they may be values for placeholders.
+                    if !self.has_placeholders(alias, group, entry) {
+                        args.push("--");
                     }
+                    shell.function(&name, program, &args)
                 }
-            }
-            None => println!("Group not found"),
+            };
+            lines.push(definition);
+            names.push(name);
         }
+        Ok(lines.into_iter().map(|line| line + "\n").collect())
     }
 
-    pub fn show(&mut self, alias: &str, group_name: Option<&str>) {
-        match self.get_group(group_name) {
-            Some(alias_map) => {
-                if alias_map.contains_key(alias) {
-                    println!("{}: {}", alias, alias_map.get(alias).unwrap());
-                } else {
-                    println!("Alias not found");
+    // Adds imported `(name, command)` pairs to a group. Returns how many were
+    // added and a line per alias that was not imported as given.
+    pub fn import(
+        &mut self,
+        aliases: &[(String, String)],
+        group_name: Option<&str>,
+        on_conflict: OnConflict,
+    ) -> Result<(usize, Vec<String>), Error> {
+        group_name.map_or(Ok(()), Self::check_name)?;
+        if on_conflict == OnConflict::Abort {
+            if let Some((alias, _)) = aliases
+                .iter()
+                .find(|(alias, _)| self.find(alias, group_name).is_some())
+            {
+                return Err(Error::AliasExists {
+                    alias: alias.clone(),
+                    group: group_name.map(str::to_string),
+                });
+            }
+        }
+        let mut imported = 0;
+        let mut report = Vec::new();
+        for (alias, command) in aliases {
+            if let Err(err) = Self::check_name(alias) {
+                report.push(format!("skipped '{}': {}", alias, err));
+                continue;
+            }
+            let mut name = alias.clone();
+            if self.find(alias, group_name).is_some() {
+                match on_conflict {
+                    OnConflict::Skip | OnConflict::Abort => {
+                        report.push(format!("skipped '{}': alias already exists", alias));
+                        continue;
+                    }
+                    OnConflict::Overwrite => {
+                        report.push(format!("overwrote '{}'", alias));
+                    }
+                    OnConflict::Rename => {
+                        name = (2..)
+                            .map(|n| format!("{}_{}", alias, n))
+                            .find(|name| self.find(name, group_name).is_none())
+                            .unwrap_or_default();
+                        report.push(format!("imported '{}' as '{}'", alias, name));
+                    }
                 }
             }
-            None => println!("Group not found"),
+            // Rc files hold plain shell commands; escape what would
+            // otherwise read as placeholders or references.
+            let entry = AliasEntry::Table(Box::new(AliasTable {
+                command: Some(Self::escape_references(&escape_template(command))),
+                ..AliasTable::default()
+            }));
+            self.add(&name, entry, group_name)?;
+            imported += 1;
         }
+        Ok((imported, report))
     }
 
-    pub fn execute(&mut self, alias: &str, group_name: Option<&str>) {
-        match self.get_group(group_name) {
-            Some(alias_map) => {
-                if alias_map.contains_key(alias) {
-                    alias_map.get_mut(alias).map(|c| run_command(c));
-                } else {
-                    println!("Alias not found");
-                }
+    pub fn show(
+        &mut self,
+        alias: &str,
+        group_name: Option<&str>,
+        output: OutputFormat,
+    ) -> Result<(), Error> {
+        let (alias, group_name) = self.resolve(alias, group_name)?;
+        let (alias, group_name) = self
+            .auto_run_target(&alias, group_name.as_deref())
+            .unwrap_or((alias, group_name));
+        let (alias, group_name) = (alias.as_str(), group_name.as_deref());
+        let entry = self.require_alias(alias, group_name)?;
+        if output != OutputFormat::Plain {
+            let record = self.record(alias, group_name, &entry);
+            print!("{}", render_record(&record, output)?);
+            return Ok(());
+        }
+        if entry.is_multi_step() {
+            println!("{}:", alias);
+            for (index, step) in entry.steps().iter().enumerate() {
+                println!("  {}. {}", index + 1, step);
             }
-            None => println!("Group not found"),
+        } else {
+            println!("{}: {}", alias, entry);
+        }
+        for (label, value) in entry.details() {
+            println!("  {}: {}", label, value);
+        }
+        if let Some(scope) = self.origin(alias, group_name) {
+            println!("  scope: {}", scope);
+        }
+        Ok(())
+    }
+
+    // Resolves an alias into the steps it runs, with references and
+    // placeholders expanded, together with its effective run options.
+    fn prepare(
+        &mut self,
+        alias: &str,
+        group_name: Option<&str>,
+        args: &TemplateArgs,
+        options: &RunOptions,
+    ) -> Result<(Vec<Step>, RunOptions), Error> {
+        let (alias, group_name) = self.resolve(alias, group_name)?;
+        let (alias, group_name) = self
+            .auto_run_target(&alias, group_name.as_deref())
+            .unwrap_or((alias, group_name));
+        let (alias, group_name) = (alias.as_str(), group_name.as_deref());
+        let entry = self.require_alias(alias, group_name)?;
+        let steps = entry.steps();
+        let mut stack = vec![Self::address(alias, group_name)];
+        let commands = steps
+            .iter()
+            .map(|step| self.expand_references(step.run(), group_name, &mut stack))
+            .collect::<Result<Vec<String>, Error>>()?;
+        let commands = expand_all(&commands, args)?;
+        let steps = steps
+            .iter()
+            .zip(commands)
+            .map(|(step, run)| Step::Detailed {
+                run,
+                continue_on_error: step.continue_on_error(),
+            })
+            .collect();
+        Ok((steps, entry.run_options(options)))
+    }
+
+    pub fn execute(
+        &mut self,
+        alias: &str,
+        group_name: Option<&str>,
+        args: &TemplateArgs,
+        options: &RunOptions,
+    ) -> Result<i32, Error> {
+        let (steps, options) = self.prepare(alias, group_name, args, options)?;
+        if let [step] = steps.as_slice() {
+            return run_command(step.run(), &options);
+        }
+        run_steps(&steps, &options)
+    }
+
+    pub fn execute_captured(
+        &mut self,
+        alias: &str,
+        group_name: Option<&str>,
+        args: &TemplateArgs,
+    ) -> Result<Vec<(Step, CommandOutput)>, Error> {
+        let (steps, options) = self.prepare(alias, group_name, args, &RunOptions::default())?;
+        let mut outputs = Vec::new();
+        for step in steps {
+            let output = capture_command(step.run(), &options)?;
+            let stop = output.code != 0 && !step.continue_on_error();
+            outputs.push((step, output));
+            if stop {
+                break;
+            }
+        }
+        Ok(outputs)
+    }
+
+    pub fn find(&self, alias: &str, group_name: Option<&str>) -> Option<&AliasEntry> {
+        match group_name {
+            Some(group) => self.alias.group.as_ref()?.get(group)?.get(alias),
+            None => self.alias.general.get(alias),
         }
     }
+
+    pub fn group_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .alias
+            .group
+            .iter()
+            .flat_map(|group_map| group_map.keys().cloned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub fn alias_names(&self, group_name: Option<&str>) -> Vec<String> {
+        self.entries()
+            .into_iter()
+            .filter(|(group, _, _)| *group == group_name)
+            .map(|(_, alias, _)| alias.to_string())
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -145,20 +1136,22 @@ mod test {
 
     fn mock_toml_config() -> TomlConfig {
         let mut general = HashMap::new();
-        general.insert("ls".to_string(), "ls -l".to_string());
-        general.insert("ll".to_string(), "ls -al".to_string());
+        general.insert("ls".to_string(), AliasEntry::from("ls -l"));
+        general.insert("ll".to_string(), AliasEntry::from("ls -al"));
         let mut aws = HashMap::new();
         let group_name = "aws";
-        let mut group: HashMap<String, HashMap<String, String>> = HashMap::new();
-        aws.insert("aws_help".to_string(), "aws --help".to_string());
-        aws.insert("aws_version".to_string(), "aws --version".to_string());
+        let mut group: HashMap<String, HashMap<String, AliasEntry>> = HashMap::new();
+        aws.insert("aws_help".to_string(), AliasEntry::from("aws --help"));
+        aws.insert("aws_version".to_string(), AliasEntry::from("aws --version"));
         group.insert(group_name.to_string(), aws.clone());
-        return TomlConfig {
+        TomlConfig {
+            settings: Settings::default(),
             alias: AliasConfig {
                 general,
                 group: Some(group),
             },
-        };
+            ..TomlConfig::default()
+        }
     }
 
     #[test]
@@ -178,20 +1171,47 @@ mod test {
         let toml_config = load_from_file(&mock, file_path).unwrap();
         assert_eq!(
             toml_config.alias.general.get("ls"),
-            Some(&"ls -l".to_string())
+            Some(&AliasEntry::from("ls -l"))
         );
     }
 
+    #[test]
+    fn test_load_or_default() {
+        let mut mock = MockFileReader::new();
+        mock.expect_read_to_string()
+            .with(eq("missing.toml"))
+            .returning(|_| Err(io::Error::from(io::ErrorKind::NotFound)));
+        mock.expect_read_to_string()
+            .with(eq("empty.toml"))
+            .returning(|_| Ok("[settings]\nbackups = 1\n".to_string()));
+        mock.expect_read_to_string()
+            .with(eq("denied.toml"))
+            .returning(|_| Err(io::Error::from(io::ErrorKind::PermissionDenied)));
+        let toml_config = load_or_default(&mock, "missing.toml".to_string()).unwrap();
+        assert!(toml_config.entries().is_empty());
+        let toml_config = load_or_default(&mock, "empty.toml".to_string()).unwrap();
+        assert!(toml_config.entries().is_empty());
+        assert_eq!(toml_config.settings.backups(), 1);
+        assert!(matches!(
+            load_or_default(&mock, "denied.toml".to_string()),
+            Err(Error::Io { .. })
+        ));
+    }
+
     #[test]
     fn test_save_to_file() {
         let mut mock = MockFileReader::new();
         let file_path = "toml_config.toml".to_string();
         let toml_config = TomlConfig {
+            settings: Settings::default(),
             alias: AliasConfig {
                 general: HashMap::new(),
                 group: None,
             },
+            ..TomlConfig::default()
         };
+        mock.expect_read_to_string()
+            .returning(|_| Err(io::Error::from(io::ErrorKind::NotFound)));
         mock.expect_write().times(1).returning(|_, _| Ok(()));
         assert_eq!(
             (),
@@ -199,14 +1219,76 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_save_to_file_rotates_backups() {
+        let mut mock = MockFileReader::new();
+        let mut sequence = mockall::Sequence::new();
+        mock.expect_read_to_string()
+            .returning(|_| Ok("[alias.general]\n".to_string()));
+        for (from, to) in [(2, 3), (1, 2)] {
+            mock.expect_rename()
+                .with(
+                    eq(format!("config.toml.bak.{}", from)),
+                    eq(format!("config.toml.bak.{}", to)),
+                )
+                .times(1)
+                .in_sequence(&mut sequence)
+                .returning(|_, _| Err(io::Error::from(io::ErrorKind::NotFound)));
+        }
+        mock.expect_write()
+            .with(eq("config.toml.bak.1"), eq("[alias.general]\n"))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_, _| Ok(()));
+        mock.expect_write()
+            .with(eq("config.toml"), mockall::predicate::always())
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_, _| Ok(()));
+        save_to_file(&mock, "config.toml".to_string(), &mock_toml_config()).unwrap();
+
+        let toml_config = mock_toml_config();
+        let content = toml::to_string(&toml_config).unwrap();
+        let mut mock = MockFileReader::new();
+        mock.expect_read_to_string()
+            .returning(move |_| Ok(content.clone()));
+        mock.expect_rename().never();
+        mock.expect_write().never();
+        save_to_file(&mock, "config.toml".to_string(), &toml_config).unwrap();
+    }
+
+    #[test]
+    fn test_save_to_file_writes_only_given_settings() {
+        let previous = "[settings]\nbackups = 5\n";
+        let mut toml_config: TomlConfig = toml::from_str(previous).unwrap();
+        toml_config.add("ll", "ls -al", None).unwrap();
+        let mut mock = MockFileReader::new();
+        mock.expect_read_to_string()
+            .returning(move |_| Ok(previous.to_string()));
+        mock.expect_rename()
+            .returning(|_, _| Err(io::Error::from(io::ErrorKind::NotFound)));
+        mock.expect_write()
+            .with(eq("config.toml.bak.1"), eq(previous))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock.expect_write()
+            .with(
+                eq("config.toml"),
+                eq("[settings]\nbackups = 5\n\n[alias.general]\nll = \"ls -al\"\n"),
+            )
+            .times(1)
+            .returning(|_, _| Ok(()));
+        save_to_file(&mock, "config.toml".to_string(), &toml_config).unwrap();
+    }
+
     #[test]
     fn test_toml_config_get_group_with_no_group_name() {
         let mut _toml_config = mock_toml_config().clone();
         assert_eq!(
             _toml_config.get_group(None).unwrap(),
             &HashMap::from([
-                ("ls".to_string(), "ls -l".to_string()),
-                ("ll".to_string(), "ls -al".to_string()),
+                ("ls".to_string(), AliasEntry::from("ls -l")),
+                ("ll".to_string(), AliasEntry::from("ls -al")),
             ])
         );
     }
@@ -218,8 +1300,8 @@ mod test {
         assert_eq!(
             _toml_config.get_group(Some(group_name)).unwrap(),
             &HashMap::from([
-                ("aws_help".to_string(), "aws --help".to_string()),
-                ("aws_version".to_string(), "aws --version".to_string()),
+                ("aws_help".to_string(), AliasEntry::from("aws --help")),
+                ("aws_version".to_string(), AliasEntry::from("aws --version")),
             ])
         );
     }
@@ -227,10 +1309,10 @@ mod test {
     #[test]
     fn test_toml_config_add() {
         let mut _toml_config = mock_toml_config().clone();
-        _toml_config.add("ls", "ls -l", None);
+        _toml_config.add("ls", "ls -l", None).unwrap();
         assert_eq!(
             _toml_config.alias.general.get("ls"),
-            Some(&"ls -l".to_string())
+            Some(&AliasEntry::from("ls -l"))
         );
     }
 
@@ -238,7 +1320,7 @@ mod test {
     fn test_toml_config_add_with_group() {
         let mut _toml_config = mock_toml_config().clone();
         let group_name = MOCK_GROUP_NAME;
-        _toml_config.add("ls", "ls -l", Some(group_name));
+        _toml_config.add("ls", "ls -l", Some(group_name)).unwrap();
         assert_eq!(
             _toml_config
                 .alias
@@ -247,7 +1329,7 @@ mod test {
                 .get(group_name)
                 .unwrap()
                 .get("ls"),
-            Some(&"ls -l".to_string())
+            Some(&AliasEntry::from("ls -l"))
         );
     }
 
@@ -255,7 +1337,7 @@ mod test {
     fn test_toml_config_add_exist_command() {
         let mut _toml_config = mock_toml_config().clone();
         let group_name = MOCK_GROUP_NAME;
-        _toml_config.add("ls", "ls -al", Some(group_name));
+        _toml_config.add("ls", "ls -al", Some(group_name)).unwrap();
         assert_eq!(
             _toml_config
                 .alias
@@ -264,19 +1346,19 @@ mod test {
                 .get(group_name)
                 .unwrap()
                 .get("ls"),
-            Some(&"ls -al".to_string())
+            Some(&AliasEntry::from("ls -al"))
         );
     }
 
     #[test]
     fn test_toml_config_list() {
         let mut _toml_config = mock_toml_config().clone();
-        _toml_config.list(None);
+        _toml_config.list(None, OutputFormat::Plain).unwrap();
         assert_eq!(
             _toml_config.alias.general,
             HashMap::from([
-                ("ls".to_string(), "ls -l".to_string()),
-                ("ll".to_string(), "ls -al".to_string()),
+                ("ls".to_string(), AliasEntry::from("ls -l")),
+                ("ll".to_string(), AliasEntry::from("ls -al")),
             ])
         );
     }
@@ -285,12 +1367,14 @@ mod test {
     fn test_toml_config_list_with_group() {
         let mut _toml_config = mock_toml_config().clone();
         let group_name = MOCK_GROUP_NAME;
-        _toml_config.list(Some(group_name));
+        _toml_config
+            .list(Some(group_name), OutputFormat::Plain)
+            .unwrap();
         assert_eq!(
             _toml_config.alias.group.unwrap().get(group_name).unwrap(),
             &HashMap::from([
-                ("aws_help".to_string(), "aws --help".to_string()),
-                ("aws_version".to_string(), "aws --version".to_string()),
+                ("aws_help".to_string(), AliasEntry::from("aws --help")),
+                ("aws_version".to_string(), AliasEntry::from("aws --version")),
             ])
         );
     }
@@ -298,12 +1382,12 @@ mod test {
     #[test]
     fn test_toml_config_show() {
         let mut _toml_config = mock_toml_config().clone();
-        _toml_config.list(None);
+        _toml_config.list(None, OutputFormat::Plain).unwrap();
         assert_eq!(
             _toml_config.alias.general,
             HashMap::from([
-                ("ls".to_string(), "ls -l".to_string()),
-                ("ll".to_string(), "ls -al".to_string()),
+                ("ls".to_string(), AliasEntry::from("ls -l")),
+                ("ll".to_string(), AliasEntry::from("ls -al")),
             ])
         );
     }
@@ -312,12 +1396,14 @@ mod test {
     fn test_toml_config_show_with_group() {
         let mut _toml_config = mock_toml_config().clone();
         let group_name = MOCK_GROUP_NAME;
-        _toml_config.list(Some(group_name));
+        _toml_config
+            .list(Some(group_name), OutputFormat::Plain)
+            .unwrap();
         assert_eq!(
             _toml_config.alias.group.unwrap().get(group_name).unwrap(),
             &HashMap::from([
-                ("aws_help".to_string(), "aws --help".to_string()),
-                ("aws_version".to_string(), "aws --version".to_string()),
+                ("aws_help".to_string(), AliasEntry::from("aws --help")),
+                ("aws_version".to_string(), AliasEntry::from("aws --version")),
             ])
         );
     }
@@ -325,7 +1411,7 @@ mod test {
     #[test]
     fn test_toml_config_remove() {
         let mut _toml_config = mock_toml_config().clone();
-        _toml_config.remove("ls", None);
+        _toml_config.remove("ls", None).unwrap();
         assert_eq!(_toml_config.alias.general.get("ls"), None);
     }
 
@@ -333,7 +1419,10 @@ mod test {
     fn test_toml_config_remove_with_group() {
         let group_name = MOCK_GROUP_NAME;
         let mut _toml_config = mock_toml_config().clone();
-        _toml_config.remove("ls", Some(group_name));
+        assert!(matches!(
+            _toml_config.remove("ls", Some(group_name)),
+            Err(Error::AliasNotFound { .. })
+        ));
         assert_eq!(
             _toml_config
                 .alias
@@ -349,13 +1438,590 @@ mod test {
     #[test]
     fn test_toml_config_execute() {
         let mut _toml_config = mock_toml_config().clone();
-        _toml_config.execute("ls", None);
+        let code =
+            _toml_config.execute("ls", None, &TemplateArgs::default(), &RunOptions::default());
+        assert_eq!(code.unwrap(), 0);
     }
 
     #[test]
     fn test_toml_config_execute_with_group() {
         let group_name = MOCK_GROUP_NAME;
         let mut _toml_config = mock_toml_config().clone();
-        _toml_config.execute("ls", Some(group_name));
+        let code = _toml_config.execute(
+            "ls",
+            Some(group_name),
+            &TemplateArgs::default(),
+            &RunOptions::default(),
+        );
+        assert!(matches!(code, Err(Error::AliasNotFound { .. })));
+    }
+
+    #[test]
+    fn test_toml_config_execute_with_unknown_group() {
+        let mut _toml_config = mock_toml_config().clone();
+        let code = _toml_config.execute(
+            "ls",
+            Some("unknown"),
+            &TemplateArgs::default(),
+            &RunOptions::default(),
+        );
+        assert!(matches!(code, Err(Error::GroupNotFound { .. })));
+    }
+
+    #[test]
+    fn test_toml_config_show_not_found() {
+        let mut _toml_config = mock_toml_config().clone();
+        assert!(matches!(
+            _toml_config.show("missing", None, OutputFormat::Plain),
+            Err(Error::AliasNotFound { .. })
+        ));
+        assert!(matches!(
+            _toml_config.show("ls", Some("missing"), OutputFormat::Plain),
+            Err(Error::GroupNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_toml_config_execute_with_missing_params() {
+        let mut _toml_config = mock_toml_config().clone();
+        _toml_config.add("greet", "echo {name}", None).unwrap();
+        let code = _toml_config.execute(
+            "greet",
+            None,
+            &TemplateArgs::default(),
+            &RunOptions::default(),
+        );
+        assert!(matches!(code, Err(Error::Template(_))));
+    }
+
+    #[test]
+    fn test_toml_config_expand_references() {
+        let mut _toml_config = mock_toml_config().clone();
+        _toml_config
+            .add("deploy", "@ls && @aws/aws_version; echo a@b @@c", None)
+            .unwrap();
+        _toml_config
+            .add("both", "@aws_help | @ll", Some(MOCK_GROUP_NAME))
+            .unwrap();
+        let mut stack = vec![];
+        assert_eq!(
+            _toml_config
+                .expand_references("@deploy", None, &mut stack)
+                .unwrap(),
+            "ls -l && aws --version; echo a@b @c"
+        );
+        assert_eq!(
+            _toml_config
+                .expand_references("@both", Some(MOCK_GROUP_NAME), &mut stack)
+                .unwrap(),
+            "aws --help | ls -al"
+        );
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn test_toml_config_expand_references_skips_single_quotes() {
+        let _toml_config = mock_toml_config().clone();
+        let mut stack = vec![];
+        assert_eq!(
+            _toml_config
+                .expand_references("echo 'mail me @ls' \"it's\" @ls", None, &mut stack)
+                .unwrap(),
+            "echo 'mail me @ls' \"it's\" ls -l"
+        );
+    }
+
+    #[test]
+    fn test_toml_config_expand_references_cycle() {
+        let mut _toml_config = mock_toml_config().clone();
+        _toml_config.add("a", "echo a && @b", None).unwrap();
+        _toml_config.add("b", "@a", None).unwrap();
+        let code =
+            _toml_config.execute("a", None, &TemplateArgs::default(), &RunOptions::default());
+        match code {
+            Err(Error::AliasCycle(chain)) => assert_eq!(chain, vec!["a", "b", "a"]),
+            _ => panic!("expected alias cycle"),
+        }
+    }
+
+    #[test]
+    fn test_toml_config_expand_references_depth() {
+        let mut _toml_config = mock_toml_config().clone();
+        for i in 0..=MAX_ALIAS_DEPTH {
+            _toml_config
+                .add(&format!("a{}", i), format!("@a{}", i + 1).as_str(), None)
+                .unwrap();
+        }
+        _toml_config
+            .add(&format!("a{}", MAX_ALIAS_DEPTH + 1), "true", None)
+            .unwrap();
+        let mut stack = vec![];
+        assert!(matches!(
+            _toml_config.expand_references("@a0", None, &mut stack),
+            Err(Error::AliasDepthExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn test_toml_config_expand_references_not_found() {
+        let _toml_config = mock_toml_config().clone();
+        for command in [
+            "@missing",
+            "curl -d @payload.json https://example.com",
+            "npm i -g @angular/cli",
+            "echo @aws/missing",
+        ] {
+            assert_eq!(
+                _toml_config
+                    .expand_references(command, None, &mut vec![])
+                    .unwrap(),
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn test_toml_config_execute_steps() {
+        let content = r#"
+            [alias.general]
+            deploy = { steps = ["true", { run = "exit 3", continue_on_error = true }, "exit 5", "true"] }
+            release = "@deploy"
+        "#;
+        let mut _toml_config: TomlConfig = toml::from_str(content).unwrap();
+        if !cfg!(target_os = "windows") {
+            let code = _toml_config.execute(
+                "deploy",
+                None,
+                &TemplateArgs::default(),
+                &RunOptions::default(),
+            );
+            assert_eq!(code.unwrap(), 5);
+        }
+        assert!(matches!(
+            _toml_config.execute(
+                "release",
+                None,
+                &TemplateArgs::default(),
+                &RunOptions::default()
+            ),
+            Err(Error::MultiStepReference(_))
+        ));
+    }
+
+    #[test]
+    fn test_toml_config_remove_prunes_empty_group() {
+        let mut _toml_config = mock_toml_config().clone();
+        _toml_config
+            .remove("aws_help", Some(MOCK_GROUP_NAME))
+            .unwrap();
+        assert!(_toml_config.get_group(Some(MOCK_GROUP_NAME)).is_some());
+        _toml_config
+            .remove("aws_version", Some(MOCK_GROUP_NAME))
+            .unwrap();
+        assert!(_toml_config.get_group(Some(MOCK_GROUP_NAME)).is_none());
+        assert!(_toml_config.alias.group.is_none());
+    }
+
+    #[test]
+    fn test_toml_config_create_group() {
+        let mut _toml_config = mock_toml_config().clone();
+        _toml_config.create_group("k8s").unwrap();
+        assert_eq!(_toml_config.group_names(), vec!["aws", "k8s"]);
+        assert!(matches!(
+            _toml_config.create_group("k8s"),
+            Err(Error::GroupExists(_))
+        ));
+    }
+
+    #[test]
+    fn test_toml_config_delete_group() {
+        let mut _toml_config = mock_toml_config().clone();
+        assert!(matches!(
+            _toml_config.delete_group(MOCK_GROUP_NAME, false),
+            Err(Error::GroupNotEmpty { aliases: 2, .. })
+        ));
+        _toml_config.delete_group(MOCK_GROUP_NAME, true).unwrap();
+        assert!(_toml_config.group_names().is_empty());
+        assert!(matches!(
+            _toml_config.delete_group(MOCK_GROUP_NAME, true),
+            Err(Error::GroupNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_toml_config_rename_group() {
+        let mut _toml_config = mock_toml_config().clone();
+        _toml_config.create_group("k8s").unwrap();
+        assert!(matches!(
+            _toml_config.rename_group(MOCK_GROUP_NAME, "k8s"),
+            Err(Error::GroupExists(_))
+        ));
+        _toml_config
+            .rename_group(MOCK_GROUP_NAME, "amazon")
+            .unwrap();
+        assert_eq!(_toml_config.group_names(), vec!["amazon", "k8s"]);
+        assert!(_toml_config.contains("aws_help", Some("amazon")));
+    }
+
+    #[test]
+    fn test_toml_config_rename() {
+        let mut _toml_config = mock_toml_config().clone();
+        _toml_config.rename("ls", None, "list", false).unwrap();
+        assert!(!_toml_config.contains("ls", None));
+        assert!(_toml_config.contains("list", None));
+        assert!(matches!(
+            _toml_config.rename("list", None, "ll", false),
+            Err(Error::AliasExists { .. })
+        ));
+        _toml_config.rename("list", None, "ll", true).unwrap();
+        assert_eq!(
+            _toml_config.alias.general,
+            HashMap::from([("ll".to_string(), AliasEntry::from("ls -l"))])
+        );
+    }
+
+    #[test]
+    fn test_toml_config_move_to() {
+        let mut _toml_config = mock_toml_config().clone();
+        _toml_config
+            .move_to("ls", None, Some(MOCK_GROUP_NAME), false)
+            .unwrap();
+        assert!(_toml_config.contains("ls", Some(MOCK_GROUP_NAME)));
+        assert!(!_toml_config.contains("ls", None));
+        _toml_config
+            .move_to("aws_help", Some(MOCK_GROUP_NAME), Some("k8s"), false)
+            .unwrap();
+        assert!(_toml_config.contains("aws_help", Some("k8s")));
+        assert!(matches!(
+            _toml_config.move_to("ls", Some(MOCK_GROUP_NAME), Some(MOCK_GROUP_NAME), false),
+            Err(Error::AliasExists { .. })
+        ));
+    }
+
+    #[test]
+    fn test_toml_config_rejects_invalid_names() {
+        let mut _toml_config = mock_toml_config().clone();
+        assert!(matches!(
+            _toml_config.add("", "ls", None),
+            Err(Error::InvalidName(_))
+        ));
+        assert!(matches!(
+            _toml_config.add("a/b/c", "ls", None),
+            Err(Error::InvalidName(_))
+        ));
+        assert!(matches!(
+            _toml_config.rename("ls", None, "a/b", false),
+            Err(Error::InvalidName(_))
+        ));
+        assert!(matches!(
+            _toml_config.move_to("ls", None, Some(""), false),
+            Err(Error::InvalidName(_))
+        ));
+        assert!(matches!(
+            _toml_config.create_group("k8s/prod"),
+            Err(Error::InvalidName(_))
+        ));
+        assert!(matches!(
+            _toml_config.rename_group(MOCK_GROUP_NAME, ""),
+            Err(Error::InvalidName(_))
+        ));
+        assert!(_toml_config.contains("ls", None));
+        assert_eq!(_toml_config.group_names(), vec![MOCK_GROUP_NAME]);
+    }
+
+    #[test]
+    fn test_toml_config_move_prunes_empty_group() {
+        let mut _toml_config = mock_toml_config().clone();
+        _toml_config
+            .move_to("aws_help", Some(MOCK_GROUP_NAME), None, false)
+            .unwrap();
+        _toml_config
+            .move_to("aws_version", Some(MOCK_GROUP_NAME), None, false)
+            .unwrap();
+        assert!(_toml_config.group_names().is_empty());
+        assert_eq!(_toml_config.alias.general.len(), 4);
+    }
+
+    #[test]
+    fn test_toml_config_copy() {
+        let mut _toml_config = mock_toml_config().clone();
+        _toml_config
+            .copy(("ls", None), ("ls", Some(MOCK_GROUP_NAME)), false)
+            .unwrap();
+        assert!(_toml_config.contains("ls", None));
+        assert!(_toml_config.contains("ls", Some(MOCK_GROUP_NAME)));
+        assert!(matches!(
+            _toml_config.copy(("ll", None), ("ls", None), false),
+            Err(Error::AliasExists { .. })
+        ));
+        _toml_config.copy(("ll", None), ("ls", None), true).unwrap();
+        assert_eq!(
+            _toml_config.alias.general.get("ls"),
+            Some(&AliasEntry::from("ls -al"))
+        );
+    }
+
+    #[test]
+    fn test_toml_config_search_hits() {
+        let mut _toml_config = mock_toml_config().clone();
+        let entry: AliasEntry = toml::from_str(
+            r#"
+            command = "kubectl get pods"
+            tags = ["k8s", "aws"]
+        "#,
+        )
+        .unwrap();
+        _toml_config.add("pods", entry, Some("k8s")).unwrap();
+        let matcher = Matcher::new("aws", MatchMode::Substring).unwrap();
+        let addresses: Vec<String> = _toml_config
+            .search_hits(&matcher, MatchMode::Substring)
+            .iter()
+            .map(|hit| TomlConfig::address(&hit.alias, hit.group.as_deref()))
+            .collect();
+        assert_eq!(
+            addresses,
+            vec!["aws/aws_help", "aws/aws_version", "k8s/pods"]
+        );
+
+        let matcher = Matcher::new("-al$", MatchMode::Regex).unwrap();
+        let hits = _toml_config.search_hits(&matcher, MatchMode::Regex);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].alias, "ll");
+        assert_eq!(hits[0].command_match.as_ref().unwrap().ranges[0], 3..6);
+
+        let matcher = Matcher::new("awv", MatchMode::Fuzzy).unwrap();
+        let hits = _toml_config.search_hits(&matcher, MatchMode::Fuzzy);
+        assert_eq!(hits[0].alias, "aws_version");
+    }
+
+    #[test]
+    fn test_toml_config_resolve_without_group() {
+        let mut _toml_config = mock_toml_config().clone();
+        assert_eq!(
+            _toml_config.resolve("aws_help", None).unwrap(),
+            ("aws_help".to_string(), Some(MOCK_GROUP_NAME.to_string()))
+        );
+        assert_eq!(
+            _toml_config.resolve("ls", None).unwrap(),
+            ("ls".to_string(), None)
+        );
+        _toml_config
+            .add("gcp/aws_help", "gcloud help", None)
+            .unwrap();
+        assert!(_toml_config.contains("aws_help", Some("gcp")));
+        match _toml_config.show("aws_help", None, OutputFormat::Plain) {
+            Err(Error::AmbiguousAlias { candidates, .. }) => {
+                assert_eq!(candidates, vec!["aws/aws_help", "gcp/aws_help"])
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        _toml_config
+            .show("gcp/aws_help", None, OutputFormat::Plain)
+            .unwrap();
+        _toml_config.remove("aws_version", None).unwrap();
+        assert!(!_toml_config.contains("aws_version", Some(MOCK_GROUP_NAME)));
+        assert!(matches!(
+            _toml_config.remove("missing", None),
+            Err(Error::AliasNotFound { group: None, .. })
+        ));
+    }
+
+    #[test]
+    fn test_toml_config_suggestions() {
+        let mut _toml_config = mock_toml_config().clone();
+        match _toml_config.show("aws_hlp", None, OutputFormat::Plain) {
+            Err(Error::AliasNotFound { suggestions, .. }) => {
+                assert_eq!(suggestions, vec!["aws/aws_help"])
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match _toml_config.remove("ls", Some("awz")) {
+            Err(Error::GroupNotFound { suggestions, .. }) => assert_eq!(suggestions, vec!["aws"]),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_toml_config_auto_run_suggestion() {
+        let mut _toml_config = mock_toml_config().clone();
+        let args = TemplateArgs::default();
+        let options = RunOptions::default();
+        assert!(matches!(
+            _toml_config.execute("lss", None, &args, &options),
+            Err(Error::AliasNotFound { .. })
+        ));
+        _toml_config.settings.auto_run_suggestion = Some(true);
+        assert_eq!(
+            _toml_config.execute("lss", None, &args, &options).unwrap(),
+            0
+        );
+        assert!(matches!(
+            _toml_config.execute("l", None, &args, &options),
+            Err(Error::AliasNotFound { .. })
+        ));
+        assert!(matches!(
+            _toml_config.execute("lss", Some(MOCK_GROUP_NAME), &args, &options),
+            Err(Error::AliasNotFound { .. })
+        ));
+        _toml_config
+            .add("true", "true", Some(MOCK_GROUP_NAME))
+            .unwrap();
+        assert_eq!(
+            _toml_config
+                .execute("tru", Some(MOCK_GROUP_NAME), &args, &options)
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_toml_config_settings() {
+        let toml_config: TomlConfig = toml::from_str(
+            r#"
+            [settings]
+            auto_run_suggestion = true
+            [alias.general]
+        "#,
+        )
+        .unwrap();
+        assert!(toml_config.settings.auto_run_suggestion());
+        let content = toml::to_string(&mock_toml_config()).unwrap();
+        assert!(!content.contains("settings"));
+    }
+
+    #[test]
+    fn test_toml_config_export() {
+        let mut _toml_config = mock_toml_config().clone();
+        _toml_config.add("greet", "echo {name}", None).unwrap();
+        _toml_config.add("la", "@ls -a", None).unwrap();
+        let entry = AliasEntry::Table(Box::new(AliasTable {
+            command: Some("ls".to_string()),
+            cwd: Some("/tmp".to_string()),
+            ..AliasTable::default()
+        }));
+        _toml_config.add("lst", entry, None).unwrap();
+        _toml_config.add("my alias", "ls", None).unwrap();
+        _toml_config.add("ls", "ls -l", Some("k8s")).unwrap();
+        let script = _toml_config.export(ShellKind::Bash, None, false).unwrap();
+        let lines: Vec<&str> = script.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "greet() { rust_cli alias exec -a greet \"$@\"; }",
+                "alias la='ls -l -a'",
+                "alias ll='ls -al'",
+                "alias ls='ls -l'",
+                "lst() { rust_cli alias exec -a lst -- \"$@\"; }",
+                "# skipped my alias: not a valid shell name",
+                "alias aws_help='aws --help'",
+                "alias aws_version='aws --version'",
+                "# skipped k8s/ls: 'ls' is already defined",
+            ]
+        );
+        let script = _toml_config
+            .export(ShellKind::Fish, Some("k8s"), true)
+            .unwrap();
+        assert_eq!(script, "alias k8s_ls 'ls -l'\n");
+        assert!(matches!(
+            _toml_config.export(ShellKind::Zsh, Some("gcp"), false),
+            Err(Error::GroupNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_toml_config_import_escapes_commands() {
+        let commands = [
+            ("e", "echo {x} '{y}'"),
+            ("d", "docker ps --format \"{{.Names}}\""),
+            ("m", "echo @ls @@ a@b 'mail @ls'"),
+        ];
+        let aliases: Vec<(String, String)> = commands
+            .iter()
+            .map(|(alias, command)| (alias.to_string(), command.to_string()))
+            .collect();
+        let mut _toml_config = mock_toml_config().clone();
+        _toml_config
+            .import(&aliases, None, OnConflict::Skip)
+            .unwrap();
+        for (alias, command) in commands {
+            let (steps, _) = _toml_config
+                .prepare(
+                    alias,
+                    None,
+                    &TemplateArgs::default(),
+                    &RunOptions::default(),
+                )
+                .unwrap();
+            assert_eq!(steps[0].run(), command);
+        }
+    }
+
+    #[test]
+    fn test_toml_config_import() {
+        let aliases: Vec<(String, String)> = [("ls", "ls -G"), ("gs", "git status")]
+            .iter()
+            .map(|(alias, command)| (alias.to_string(), command.to_string()))
+            .collect();
+        let mut _toml_config = mock_toml_config().clone();
+        let (imported, report) = _toml_config
+            .import(&aliases, None, OnConflict::Skip)
+            .unwrap();
+        assert_eq!(imported, 1);
+        assert_eq!(report, vec!["skipped 'ls': alias already exists"]);
+        assert_eq!(_toml_config.find("ls", None).unwrap().to_string(), "ls -l");
+        assert_eq!(
+            _toml_config.find("gs", None).unwrap().to_string(),
+            "git status"
+        );
+
+        let mut _toml_config = mock_toml_config().clone();
+        _toml_config
+            .import(&aliases, None, OnConflict::Overwrite)
+            .unwrap();
+        assert_eq!(_toml_config.find("ls", None).unwrap().to_string(), "ls -G");
+
+        let mut _toml_config = mock_toml_config().clone();
+        let (imported, report) = _toml_config
+            .import(&aliases, None, OnConflict::Rename)
+            .unwrap();
+        assert_eq!(imported, 2);
+        assert_eq!(report, vec!["imported 'ls' as 'ls_2'"]);
+        assert_eq!(
+            _toml_config.find("ls_2", None).unwrap().to_string(),
+            "ls -G"
+        );
+
+        let mut _toml_config = mock_toml_config().clone();
+        assert!(matches!(
+            _toml_config.import(&aliases, None, OnConflict::Abort),
+            Err(Error::AliasExists { .. })
+        ));
+        assert!(_toml_config.find("gs", None).is_none());
+        _toml_config
+            .import(&aliases, Some("shell"), OnConflict::Abort)
+            .unwrap();
+        assert!(_toml_config.contains("ls", Some("shell")));
+    }
+
+    #[test]
+    fn test_resolve_config_path() {
+        let xdg = Some("/xdg".to_string());
+        let env = Some("/env/aliases.toml".to_string());
+        assert_eq!(
+            resolve_config_path(Some("/flag.toml"), env.clone(), xdg.clone()),
+            PathBuf::from("/flag.toml")
+        );
+        assert_eq!(
+            resolve_config_path(None, env, xdg.clone()),
+            PathBuf::from("/env/aliases.toml")
+        );
+        assert_eq!(
+            resolve_config_path(None, Some(String::new()), xdg),
+            PathBuf::from("/xdg/rust_cli/config.toml")
+        );
+        assert_eq!(
+            resolve_config_path(None, None, Some("relative".to_string())),
+            expand_home("~/.config/rust_cli/config.toml")
+        );
     }
 }
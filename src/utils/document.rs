@@ -0,0 +1,226 @@
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, TableLike, Value};
+
+// Serializes a map in key order so a fresh config file, and keys added to an
+// existing one, come out the same on every save.
+pub fn sorted<S: Serializer, V: Serialize>(
+    map: &HashMap<String, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+// Applies the data in `content` to the document in `previous`. Unchanged
+// values keep their comments, quoting and position; new keys are appended.
+// Falls back to `content` when either side is not valid TOML.
+pub fn preserve_format(previous: &str, content: &str) -> String {
+    let (Ok(mut document), Ok(mut updated)) = (
+        previous.parse::<DocumentMut>(),
+        content.parse::<DocumentMut>(),
+    ) else {
+        return content.to_string();
+    };
+    // Sections still at their defaults are left out of `content`; keep them.
+    for (key, item) in document.iter() {
+        if !updated.contains_key(key) {
+            updated.insert(key, item.clone());
+        }
+    }
+    update_table(document.as_table_mut(), updated.as_table());
+    document.to_string()
+}
+
+fn update_table(table: &mut dyn TableLike, updated: &dyn TableLike) {
+    let removed: Vec<String> = table
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !updated.contains_key(key))
+        .collect();
+    for key in removed {
+        table.remove(&key);
+    }
+    for (key, item) in updated.iter() {
+        match table.get_mut(key) {
+            Some(existing) if same_kind(existing, item) => update_item(existing, item),
+            // A key whose value turns into a table, or back, starts over: its
+            // decor only fits the old kind of item.
+            _ => {
+                table.remove(key);
+                table.insert(key, unpositioned(item));
+            }
+        }
+    }
+}
+
+fn same_kind(a: &Item, b: &Item) -> bool {
+    (a.is_table_like() && b.is_table_like()) || (a.is_value() && b.is_value())
+}
+
+fn update_item(existing: &mut Item, updated: &Item) {
+    if let (Some(table), Some(updated)) = (existing.as_table_like_mut(), updated.as_table_like()) {
+        update_table(table, updated);
+        return;
+    }
+    if let (Some(value), Some(updated)) = (existing.as_value_mut(), updated.as_value()) {
+        if !same_value(value, updated) {
+            let decor = value.decor().clone();
+            *value = updated.clone();
+            *value.decor_mut() = decor;
+        }
+    }
+}
+
+// Tables parsed from `content` carry their position in it. Without one, a
+// new table is written right after the table before it in the document.
+fn unpositioned(item: &Item) -> Item {
+    match item {
+        Item::Table(table) => Item::Table(unpositioned_table(table)),
+        Item::ArrayOfTables(array) => {
+            let mut fresh = ArrayOfTables::new();
+            for table in array.iter() {
+                fresh.push(unpositioned_table(table));
+            }
+            Item::ArrayOfTables(fresh)
+        }
+        item => item.clone(),
+    }
+}
+
+fn unpositioned_table(table: &Table) -> Table {
+    let mut fresh = Table::new();
+    fresh.set_implicit(table.is_implicit());
+    for (key, item) in table.iter() {
+        fresh.insert(key, unpositioned(item));
+    }
+    fresh
+}
+
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => a.value() == b.value(),
+        (Value::Integer(a), Value::Integer(b)) => a.value() == b.value(),
+        (Value::Float(a), Value::Float(b)) => a.value() == b.value(),
+        (Value::Boolean(a), Value::Boolean(b)) => a.value() == b.value(),
+        (Value::Datetime(a), Value::Datetime(b)) => a.value() == b.value(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same_value(a, b))
+        }
+        (Value::InlineTable(a), Value::InlineTable(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| same_value(a, b)))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_preserve_format() {
+        let previous = r#"# my aliases
+[alias.general]
+ll = 'ls -l'   # long listing
+gs = "git status"
+
+[alias.group.aws]
+aws_help = "aws --help"
+"#;
+        let content = r#"[alias.general]
+ll = "ls -l"
+gs = "git status -sb"
+k = "kubectl"
+
+[alias.group.gcp]
+gcp_help = "gcloud --help"
+"#;
+        assert_eq!(
+            preserve_format(previous, content),
+            r#"# my aliases
+[alias.general]
+ll = 'ls -l'   # long listing
+gs = "git status -sb"
+k = "kubectl"
+
+[alias.group.gcp]
+gcp_help = "gcloud --help"
+"#
+        );
+    }
+
+    #[test]
+    fn test_preserve_format_new_tables() {
+        let previous = r#"# header
+[settings]
+backups = 3
+
+[alias.general]
+ll = "ls -l"
+
+[alias.group.aws]
+aws_help = "aws --help"
+"#;
+        let content = r#"[alias.general]
+ll = "ls -l"
+
+[alias.general.hi]
+command = "echo hi"
+
+[alias.group.aws]
+aws_help = "aws --help"
+"#;
+        assert_eq!(
+            preserve_format(previous, content),
+            r#"# header
+[settings]
+backups = 3
+
+[alias.general]
+ll = "ls -l"
+
+[alias.general.hi]
+command = "echo hi"
+
+[alias.group.aws]
+aws_help = "aws --help"
+"#
+        );
+    }
+
+    #[test]
+    fn test_preserve_format_changed_kind() {
+        let previous = r#"[alias.general]
+# keep me
+ls = "ls -l"
+ll = "ls -al"
+"#;
+        let content = r#"[alias.general]
+ll = "ls -al"
+
+[alias.general.ls]
+command = "ls -l"
+description = "list"
+"#;
+        let output = preserve_format(previous, content);
+        assert_eq!(
+            output.parse::<toml::Table>().unwrap(),
+            content.parse::<toml::Table>().unwrap()
+        );
+        let output = preserve_format(&output, previous);
+        assert_eq!(
+            output.parse::<toml::Table>().unwrap(),
+            previous.parse::<toml::Table>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_preserve_format_unchanged() {
+        let previous = "[alias.general]\nls = { command = 'ls', steps = ['a', 'b'] } # x\n";
+        let content = "[alias.general.ls]\ncommand = \"ls\"\nsteps = [\"a\", \"b\"]\n";
+        assert_eq!(preserve_format(previous, content), previous);
+        assert_eq!(preserve_format("not toml [", content), content);
+    }
+}
@@ -0,0 +1,367 @@
+use crate::utils::{sorted, RunOptions};
+use chrono::{SecondsFormat, Utc};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Step {
+    Command(String),
+    Detailed {
+        run: String,
+        #[serde(default, skip_serializing_if = "is_false")]
+        continue_on_error: bool,
+    },
+}
+
+impl Step {
+    pub fn run(&self) -> &str {
+        match self {
+            Step::Command(run) | Step::Detailed { run, .. } => run,
+        }
+    }
+
+    pub fn set_run(&mut self, command: String) {
+        match self {
+            Step::Command(run) | Step::Detailed { run, .. } => *run = command,
+        }
+    }
+
+    pub fn continue_on_error(&self) -> bool {
+        match self {
+            Step::Command(_) => false,
+            Step::Detailed {
+                continue_on_error, ..
+            } => *continue_on_error,
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.continue_on_error() {
+            write!(f, "{} (continue on error)", self.run())
+        } else {
+            write!(f, "{}", self.run())
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AliasTable {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<Step>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted"
+    )]
+    pub env: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub clear_env: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum AliasEntry {
+    Command(String),
+    Table(Box<AliasTable>),
+}
+
+// Deserialized by hand so a bad table reports what is wrong with it instead
+// of the generic untagged-enum error, and so a table that runs nothing, such
+// as one with a misspelled `command`, is rejected.
+impl<'de> Deserialize<'de> for AliasEntry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Command(String),
+            Table(toml::Table),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Command(command) => Ok(AliasEntry::Command(command)),
+            Raw::Table(table) => {
+                let table: AliasTable = toml::Value::Table(table)
+                    .try_into()
+                    .map_err(D::Error::custom)?;
+                if table.command.is_none() && table.steps.is_empty() {
+                    return Err(D::Error::custom("alias needs a `command` or `steps`"));
+                }
+                Ok(AliasEntry::Table(Box::new(table)))
+            }
+        }
+    }
+}
+
+impl AliasEntry {
+    pub fn steps(&self) -> Vec<Step> {
+        match self {
+            AliasEntry::Command(command) => vec![Step::Command(command.clone())],
+            AliasEntry::Table(table) => {
+                let mut steps: Vec<Step> =
+                    table.command.iter().cloned().map(Step::Command).collect();
+                steps.extend(table.steps.iter().cloned());
+                steps
+            }
+        }
+    }
+
+    pub fn is_multi_step(&self) -> bool {
+        self.steps().len() > 1
+    }
+
+    pub fn table(&self) -> Option<&AliasTable> {
+        match self {
+            AliasEntry::Command(_) => None,
+            AliasEntry::Table(table) => Some(table.as_ref()),
+        }
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.table()?.description.as_deref()
+    }
+
+    pub fn tags(&self) -> &[String] {
+        self.table().map_or(&[], |table| table.tags.as_slice())
+    }
+
+    pub fn notes(&self) -> Option<String> {
+        let mut notes: Vec<String> = self.description().map(str::to_string).into_iter().collect();
+        if !self.tags().is_empty() {
+            notes.push(format!("[{}]", self.tags().join(", ")));
+        }
+        if notes.is_empty() {
+            None
+        } else {
+            Some(notes.join(" "))
+        }
+    }
+
+    // Stamps `updated_at` with the current time, carrying `created_at` over
+    // from the entry being replaced. Plain string entries have nowhere to
+    // keep timestamps and are left as they are.
+    pub fn touch(&mut self, previous: Option<&AliasEntry>) {
+        if let AliasEntry::Table(table) = self {
+            let now = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+            table.created_at = previous
+                .and_then(AliasEntry::table)
+                .and_then(|previous| previous.created_at.clone())
+                .or_else(|| table.created_at.clone())
+                .or_else(|| Some(now.clone()));
+            table.updated_at = Some(now);
+        }
+    }
+
+    pub fn details(&self) -> Vec<(&'static str, String)> {
+        let mut details = Vec::new();
+        let Some(table) = self.table() else {
+            return details;
+        };
+        if let Some(description) = &table.description {
+            details.push(("description", description.clone()));
+        }
+        if !table.tags.is_empty() {
+            details.push(("tags", table.tags.join(", ")));
+        }
+        if let Some(author) = &table.author {
+            details.push(("author", author.clone()));
+        }
+        if let Some(cwd) = &table.cwd {
+            details.push(("cwd", cwd.clone()));
+        }
+        let mut env: Vec<_> = table.env.iter().collect();
+        env.sort();
+        for (key, value) in env {
+            details.push(("env", format!("{}={}", key, value)));
+        }
+        if table.clear_env {
+            details.push(("clear_env", "true".to_string()));
+        }
+        if let Some(created_at) = &table.created_at {
+            details.push(("created_at", created_at.clone()));
+        }
+        if let Some(updated_at) = &table.updated_at {
+            details.push(("updated_at", updated_at.clone()));
+        }
+        details
+    }
+
+    pub fn run_options(&self, options: &RunOptions) -> RunOptions {
+        let mut options = options.clone();
+        if let AliasEntry::Table(table) = self {
+            options.env.extend(table.env.clone());
+            if table.cwd.is_some() {
+                options.cwd = table.cwd.clone();
+            }
+            options.clear_env |= table.clear_env;
+        }
+        options
+    }
+}
+
+impl From<&str> for AliasEntry {
+    fn from(command: &str) -> Self {
+        AliasEntry::Command(command.to_string())
+    }
+}
+
+impl fmt::Display for AliasEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let steps: Vec<String> = self.steps().iter().map(Step::to_string).collect();
+        write!(f, "{}", steps.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_deserialize_plain_and_steps() {
+        let content = r#"
+            ls = "ls -l"
+            [deploy]
+            steps = ["cargo build", { run = "cargo test", continue_on_error = true }]
+        "#;
+        let entries: HashMap<String, AliasEntry> = toml::from_str(content).unwrap();
+        assert_eq!(entries["ls"], AliasEntry::from("ls -l"));
+        let steps = entries["deploy"].steps();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].run(), "cargo build");
+        assert!(!steps[0].continue_on_error());
+        assert_eq!(steps[1].run(), "cargo test");
+        assert!(steps[1].continue_on_error());
+        assert!(entries["deploy"].is_multi_step());
+    }
+
+    #[test]
+    fn test_deserialize_rejects_bad_tables() {
+        let result =
+            toml::from_str::<HashMap<String, AliasEntry>>(r#"hi = { comand = "echo hi" }"#);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("unknown field `comand`"));
+        let result = toml::from_str::<HashMap<String, AliasEntry>>(r#"hi = { description = "x" }"#);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("alias needs a `command` or `steps`"));
+        let result = toml::from_str::<HashMap<String, AliasEntry>>("hi = 1");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        let entry = AliasEntry::Table(Box::new(AliasTable {
+            steps: vec![
+                Step::Command("cargo build".to_string()),
+                Step::Detailed {
+                    run: "cargo test".to_string(),
+                    continue_on_error: true,
+                },
+            ],
+            ..AliasTable::default()
+        }));
+        assert_eq!(
+            entry.to_string(),
+            "cargo build; cargo test (continue on error)"
+        );
+    }
+
+    #[test]
+    fn test_run_options() {
+        let content = r#"
+            command = "aws s3 ls"
+            env = { AWS_PROFILE = "dev" }
+            cwd = "~/src/infra"
+            clear_env = true
+        "#;
+        let entry: AliasEntry = toml::from_str(content).unwrap();
+        let options = entry.run_options(&RunOptions {
+            capture: true,
+            ..RunOptions::default()
+        });
+        assert!(options.capture);
+        assert!(options.clear_env);
+        assert_eq!(options.cwd.as_deref(), Some("~/src/infra"));
+        assert_eq!(
+            options.env.get("AWS_PROFILE").map(String::as_str),
+            Some("dev")
+        );
+        assert_eq!(entry.steps().len(), 1);
+    }
+
+    #[test]
+    fn test_touch_keeps_created_at() {
+        let previous: AliasEntry = toml::from_str(
+            r#"
+            command = "ls"
+            created_at = "2024-01-01T00:00:00Z"
+            updated_at = "2024-01-01T00:00:00Z"
+        "#,
+        )
+        .unwrap();
+        let mut entry = AliasEntry::Table(Box::new(AliasTable {
+            command: Some("ls -al".to_string()),
+            ..AliasTable::default()
+        }));
+        entry.touch(Some(&previous));
+        let table = entry.table().unwrap();
+        assert_eq!(table.created_at.as_deref(), Some("2024-01-01T00:00:00Z"));
+        assert_ne!(table.updated_at, table.created_at);
+
+        let mut plain = AliasEntry::from("ls");
+        plain.touch(Some(&previous));
+        assert_eq!(plain, AliasEntry::from("ls"));
+    }
+
+    #[test]
+    fn test_details() {
+        let entry: AliasEntry = toml::from_str(
+            r#"
+            command = "aws s3 ls"
+            description = "List buckets"
+            tags = ["aws", "s3"]
+            author = "ops"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(entry.description(), Some("List buckets"));
+        assert_eq!(entry.tags(), ["aws", "s3"]);
+        assert_eq!(
+            entry.details(),
+            vec![
+                ("description", "List buckets".to_string()),
+                ("tags", "aws, s3".to_string()),
+                ("author", "ops".to_string()),
+            ]
+        );
+        assert_eq!(entry.notes().as_deref(), Some("List buckets [aws, s3]"));
+        assert!(AliasEntry::from("ls").details().is_empty());
+        assert_eq!(AliasEntry::from("ls").notes(), None);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::utils::TemplateError;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    AliasNotFound {
+        alias: String,
+        group: Option<String>,
+        suggestions: Vec<String>,
+    },
+    AliasExists {
+        alias: String,
+        group: Option<String>,
+    },
+    AmbiguousAlias {
+        alias: String,
+        candidates: Vec<String>,
+    },
+    GroupNotFound {
+        group: String,
+        suggestions: Vec<String>,
+    },
+    GroupExists(String),
+    ConfigExists(String),
+    InvalidName(String),
+    GroupNotEmpty {
+        group: String,
+        aliases: usize,
+    },
+    AliasCycle(Vec<String>),
+    AliasDepthExceeded(usize),
+    MultiStepReference(String),
+    InvalidPattern(String),
+    Template(TemplateError),
+    Parse {
+        path: String,
+        source: toml::de::Error,
+    },
+    Serialize(toml::ser::Error),
+    Output(String),
+    Io {
+        path: String,
+        source: io::Error,
+    },
+    Spawn {
+        command: String,
+        source: io::Error,
+    },
+    Terminal(io::Error),
+}
+
+impl Error {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Template(_) => 2,
+            Error::AliasNotFound { .. } => 3,
+            Error::GroupNotFound { .. } => 4,
+            Error::Parse { .. } | Error::Serialize(_) | Error::Output(_) => 5,
+            Error::Io { .. } => 6,
+            Error::AliasCycle(_) => 7,
+            Error::AliasDepthExceeded(_) => 8,
+            Error::MultiStepReference(_) => 9,
+            Error::GroupExists(_) => 10,
+            Error::GroupNotEmpty { .. } => 11,
+            Error::AliasExists { .. } => 12,
+            Error::InvalidPattern(_) => 13,
+            Error::AmbiguousAlias { .. } => 14,
+            Error::Terminal(_) => 15,
+            Error::ConfigExists(_) => 16,
+            Error::InvalidName(_) => 17,
+            Error::Spawn { .. } => 127,
+        }
+    }
+}
+
+fn write_suggestions(f: &mut fmt::Formatter<'_>, suggestions: &[String]) -> fmt::Result {
+    if suggestions.is_empty() {
+        return Ok(());
+    }
+    let quoted: Vec<String> = suggestions.iter().map(|s| format!("'{}'", s)).collect();
+    write!(f, ", did you mean {}?", quoted.join(" or "))
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::AliasNotFound {
+                alias,
+                group,
+                suggestions,
+            } => {
+                match group {
+                    Some(group) => write!(f, "alias '{}' not found in group '{}'", alias, group)?,
+                    None => write!(f, "alias '{}' not found", alias)?,
+                }
+                write_suggestions(f, suggestions)
+            }
+            Error::AliasExists { alias, group } => match group {
+                Some(group) => write!(
+                    f,
+                    "alias '{}' already exists in group '{}', use --overwrite to replace it",
+                    alias, group
+                ),
+                None => write!(
+                    f,
+                    "alias '{}' already exists, use --overwrite to replace it",
+                    alias
+                ),
+            },
+            Error::AmbiguousAlias { alias, candidates } => write!(
+                f,
+                "alias '{}' is ambiguous, use one of: {}",
+                alias,
+                candidates.join(", ")
+            ),
+            Error::GroupNotFound { group, suggestions } => {
+                write!(f, "group '{}' not found", group)?;
+                write_suggestions(f, suggestions)
+            }
+            Error::GroupExists(group) => write!(f, "group '{}' already exists", group),
+            Error::GroupNotEmpty { group, aliases } => write!(
+                f,
+                "group '{}' still has {} alias(es), use --force to delete it",
+                group, aliases
+            ),
+            Error::AliasCycle(chain) => write!(f, "alias cycle detected: {}", chain.join(" -> ")),
+            Error::AliasDepthExceeded(depth) => {
+                write!(f, "alias references nested deeper than {} levels", depth)
+            }
+            Error::MultiStepReference(alias) => write!(
+                f,
+                "alias '{}' has multiple steps and cannot be referenced inline",
+                alias
+            ),
+            Error::InvalidPattern(err) => write!(f, "invalid pattern: {}", err),
+            Error::Template(err) => write!(f, "{}", err),
+            Error::Parse { path, source } => write!(f, "failed to parse {}: {}", path, source),
+            Error::Serialize(err) => write!(f, "failed to serialize config: {}", err),
+            Error::Output(err) => write!(f, "failed to format output: {}", err),
+            Error::Io { path, source } => write!(f, "{}: {}", path, source),
+            Error::Spawn { command, source } => {
+                write!(f, "failed to execute '{}': {}", command, source)
+            }
+            Error::Terminal(err) => write!(f, "terminal: {}", err),
+            Error::ConfigExists(path) => {
+                write!(f, "{} already exists, pass --force to overwrite it", path)
+            }
+            Error::InvalidName(name) => write!(
+                f,
+                "invalid name '{}', names must not be empty or contain '/'",
+                name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Template(err) => Some(err),
+            Error::Parse { source, .. } => Some(source),
+            Error::Serialize(err) => Some(err),
+            Error::Io { source, .. } | Error::Spawn { source, .. } | Error::Terminal(source) => {
+                Some(source)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<TemplateError> for Error {
+    fn from(err: TemplateError) -> Self {
+        Error::Template(err)
+    }
+}
+
+impl From<toml::ser::Error> for Error {
+    fn from(err: toml::ser::Error) -> Self {
+        Error::Serialize(err)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = Error::AliasNotFound {
+            alias: "ls".to_string(),
+            group: Some("aws".to_string()),
+            suggestions: vec![],
+        };
+        assert_eq!(err.to_string(), "alias 'ls' not found in group 'aws'");
+        let err = Error::GroupNotFound {
+            group: "awz".to_string(),
+            suggestions: vec!["aws".to_string(), "az".to_string()],
+        };
+        assert_eq!(
+            err.to_string(),
+            "group 'awz' not found, did you mean 'aws' or 'az'?"
+        );
+    }
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let errors = [
+            Error::Template(TemplateError::Missing(vec!["1".to_string()])),
+            Error::AliasNotFound {
+                alias: "ls".to_string(),
+                group: None,
+                suggestions: vec![],
+            },
+            Error::GroupNotFound {
+                group: "aws".to_string(),
+                suggestions: vec![],
+            },
+            Error::AliasExists {
+                alias: "ls".to_string(),
+                group: None,
+            },
+            Error::GroupExists("aws".to_string()),
+            Error::GroupNotEmpty {
+                group: "aws".to_string(),
+                aliases: 2,
+            },
+            Error::AliasCycle(vec!["a".to_string(), "a".to_string()]),
+            Error::AliasDepthExceeded(16),
+            Error::MultiStepReference("deploy".to_string()),
+            Error::InvalidPattern("(".to_string()),
+            Error::AmbiguousAlias {
+                alias: "help".to_string(),
+                candidates: vec!["aws/help".to_string(), "gcp/help".to_string()],
+            },
+            Error::Io {
+                path: "config.toml".to_string(),
+                source: io::Error::other("test"),
+            },
+            Error::Spawn {
+                command: "ls".to_string(),
+                source: io::Error::other("test"),
+            },
+            Error::Terminal(io::Error::other("test")),
+            Error::ConfigExists("config.toml".to_string()),
+            Error::InvalidName("a/b".to_string()),
+        ];
+        let mut codes: Vec<i32> = errors.iter().map(|err| err.exit_code()).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+        assert!(codes.iter().all(|code| *code != 0));
+    }
+}
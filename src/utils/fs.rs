@@ -1,11 +1,14 @@
+use crate::utils::state_path;
 use mockall::automock;
-use std::fs;
-use std::io;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
 
 #[automock]
 pub trait FileReader {
     fn read_to_string(&self, path: &str) -> io::Result<String>;
     fn write(&self, path: &str, content: &str) -> io::Result<()>;
+    fn rename(&self, from: &str, to: &str) -> io::Result<()>;
 }
 
 pub struct RealFileReader;
@@ -15,11 +18,80 @@ impl FileReader for RealFileReader {
         fs::read_to_string(path)
     }
 
+    // Writes a temp file next to `path` and renames it into place, so a crash
+    // leaves either the old or the new content and never a partial file.
     fn write(&self, path: &str, content: &str) -> io::Result<()> {
-        fs::write(content, path)
+        let path = Path::new(path);
+        create_parent_dir(path)?;
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+        let result =
+            write_synced(&temp_path, path, content).and_then(|_| fs::rename(&temp_path, path));
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        result?;
+        // The rename itself is only durable once the directory is synced.
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            let _ = File::open(dir).and_then(|dir| dir.sync_all());
+        }
+        Ok(())
     }
+
+    fn rename(&self, from: &str, to: &str) -> io::Result<()> {
+        fs::rename(from, to)
+    }
+}
+
+fn create_parent_dir(path: &Path) -> io::Result<()> {
+    match path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        Some(dir) => fs::create_dir_all(dir),
+        None => Ok(()),
+    }
+}
+
+fn write_synced(temp_path: &Path, path: &Path, content: &str) -> io::Result<()> {
+    let mut file = File::create(temp_path)?;
+    if let Ok(metadata) = fs::metadata(path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.write_all(content.as_bytes())?;
+    file.sync_all()
+}
+
+pub fn lock_path(path: &str) -> String {
+    format!("{}.lock", state_path(path))
+}
+
+// Advisory lock on `<path>.lock`, or the `state_path` equivalent, held until
+// dropped. Commands that change the config hold it from load to save so
+// concurrent runs don't lose writes.
+pub struct ConfigLock {
+    _file: File,
 }
 
+impl ConfigLock {
+    pub fn acquire(path: &str) -> io::Result<Self> {
+        let lock_path = lock_path(path);
+        create_parent_dir(Path::new(&lock_path))?;
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_path)?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(fs::TryLockError::WouldBlock) => {
+                eprintln!("waiting for another rust_cli to release {}", path);
+                file.lock()?;
+            }
+            Err(fs::TryLockError::Error(err)) => return Err(err),
+        }
+        Ok(ConfigLock { _file: file })
+    }
+}
+
+#[cfg(test)]
 mod test {
     use super::*;
     use mockall::predicate::eq;
@@ -39,7 +111,7 @@ mod test {
         let mut mock = MockFileReader::new();
         mock.expect_read_to_string()
             .with(eq("test.txt"))
-            .returning(|_| Err(io::Error::new(ErrorKind::Other, "test")));
+            .returning(|_| Err(io::Error::other("test")));
         assert_eq!(mock.read_to_string("test.txt").unwrap_err().kind(), ErrorKind::Other);
     }
 
@@ -57,7 +129,23 @@ mod test {
         let mut mock = MockFileReader::new();
         mock.expect_write()
             .with(eq("test.txt"), eq("test"))
-            .returning(|_, _| Err(io::Error::new(ErrorKind::Other, "test")));
+            .returning(|_, _| Err(io::Error::other("test")));
         assert_eq!(mock.write("test.txt", "test").unwrap_err().kind(), ErrorKind::Other);
     }
+
+    #[test]
+    fn test_real_write() {
+        let dir = std::env::temp_dir().join(format!("rust_cli_fs_{}", std::process::id()));
+        let path = dir.join("config.toml").display().to_string();
+        RealFileReader.write(&path, "old").unwrap();
+        RealFileReader.write(&path, "new").unwrap();
+        assert_eq!(RealFileReader.read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        let lock = ConfigLock::acquire(&path).unwrap();
+        let other = File::open(lock_path(&path)).unwrap();
+        assert!(other.try_lock().is_err());
+        drop(lock);
+        assert!(other.try_lock().is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
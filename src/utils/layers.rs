@@ -0,0 +1,212 @@
+use crate::utils::{config_path, expand_home, load_or_default, Error, FileReader, TomlConfig};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub const SYSTEM_CONFIG_PATH: &str = "/etc/rust_cli/config.toml";
+pub const PROJECT_CONFIG_NAME: &str = ".rust_cli.toml";
+
+// Config layers from lowest to highest precedence: an alias in a later layer
+// replaces the one with the same name and group in an earlier layer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    System,
+    User,
+    Project,
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scope::System => write!(f, "system"),
+            Scope::User => write!(f, "user"),
+            Scope::Project => write!(f, "project"),
+        }
+    }
+}
+
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_NAME))
+        .find(|path| path.is_file())
+}
+
+// The file a scope reads and writes. Without a project config above the
+// current directory, the project scope uses one in the current directory.
+pub fn scope_path(scope: Scope, flag: Option<&str>) -> String {
+    match scope {
+        Scope::System => SYSTEM_CONFIG_PATH.to_string(),
+        Scope::User => config_path(flag),
+        Scope::Project => {
+            let cwd = std::env::current_dir().unwrap_or_default();
+            find_project_config(&cwd)
+                .unwrap_or_else(|| cwd.join(PROJECT_CONFIG_NAME))
+                .display()
+                .to_string()
+        }
+    }
+}
+
+// Where the lock and backups of a config file go. They sit next to the file,
+// except for a project config, which usually lives in a git working tree:
+// its files are kept under `$XDG_STATE_HOME/rust_cli`, `~/.local/state` by
+// default, named after the config's absolute path with `/` replaced by `%`.
+pub fn state_path(file_path: &str) -> String {
+    resolve_state_path(file_path, std::env::var("XDG_STATE_HOME").ok())
+}
+
+fn resolve_state_path(file_path: &str, xdg_state_home: Option<String>) -> String {
+    let path = Path::new(file_path);
+    if path.file_name() != Some(OsStr::new(PROJECT_CONFIG_NAME)) {
+        return file_path.to_string();
+    }
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let name = absolute
+        .display()
+        .to_string()
+        .replace(['/', '\\', ':'], "%");
+    xdg_state_home
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .unwrap_or_else(|| expand_home("~/.local/state"))
+        .join(env!("CARGO_PKG_NAME"))
+        .join(name)
+        .display()
+        .to_string()
+}
+
+pub fn load_layers(
+    file_reader: &dyn FileReader,
+    layers: &[(Scope, String)],
+) -> Result<TomlConfig, Error> {
+    let mut toml_config = TomlConfig::default();
+    for (scope, path) in layers {
+        let layer = load_or_default(file_reader, path.clone())?;
+        toml_config.merge(layer, *scope);
+    }
+    Ok(toml_config)
+}
+
+pub fn load_merged(file_reader: &dyn FileReader, flag: Option<&str>) -> Result<TomlConfig, Error> {
+    let layers: Vec<(Scope, String)> = [Scope::System, Scope::User, Scope::Project]
+        .into_iter()
+        .map(|scope| (scope, scope_path(scope, flag)))
+        .collect();
+    load_layers(file_reader, &layers)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::MockFileReader;
+    use mockall::predicate::eq;
+
+    fn mock_file_reader() -> MockFileReader {
+        let mut mock = MockFileReader::new();
+        mock.expect_read_to_string()
+            .with(eq("system.toml"))
+            .returning(|_| {
+                Ok(r#"
+                    [settings]
+                    auto_run_suggestion = true
+                    backups = 2
+                    [alias.general]
+                    ls = "ls"
+                    ll = "ls -l"
+                "#
+                .to_string())
+            });
+        mock.expect_read_to_string()
+            .with(eq("user.toml"))
+            .returning(|_| {
+                Ok(r#"
+                    [settings]
+                    backups = 5
+                    [alias.general]
+                    ll = "ls -al"
+                    [alias.group.aws]
+                    aws_help = "aws --help"
+                    [alias.group.k8s]
+                "#
+                .to_string())
+            });
+        mock.expect_read_to_string()
+            .with(eq("project.toml"))
+            .returning(|_| Err(std::io::Error::from(std::io::ErrorKind::NotFound)));
+        mock.expect_read_to_string()
+            .with(eq("override.toml"))
+            .returning(|_| Ok("[settings]\nauto_run_suggestion = false\n".to_string()));
+        mock
+    }
+
+    #[test]
+    fn test_load_layers() {
+        let layers = [
+            (Scope::System, "system.toml".to_string()),
+            (Scope::User, "user.toml".to_string()),
+            (Scope::Project, "project.toml".to_string()),
+        ];
+        let toml_config = load_layers(&mock_file_reader(), &layers).unwrap();
+        assert_eq!(toml_config.origin("ls", None), Some(Scope::System));
+        assert_eq!(toml_config.origin("ll", None), Some(Scope::User));
+        assert_eq!(toml_config.find("ll", None).unwrap().to_string(), "ls -al");
+        assert_eq!(
+            toml_config.origin("aws_help", Some("aws")),
+            Some(Scope::User)
+        );
+        assert_eq!(toml_config.group_names(), vec!["aws", "k8s"]);
+    }
+
+    #[test]
+    fn test_load_layers_merges_settings() {
+        let layers = [
+            (Scope::System, "system.toml".to_string()),
+            (Scope::User, "user.toml".to_string()),
+        ];
+        let toml_config = load_layers(&mock_file_reader(), &layers).unwrap();
+        assert!(toml_config.settings.auto_run_suggestion());
+        assert_eq!(toml_config.settings.backups(), 5);
+        let layers = [
+            (Scope::System, "system.toml".to_string()),
+            (Scope::Project, "override.toml".to_string()),
+        ];
+        let toml_config = load_layers(&mock_file_reader(), &layers).unwrap();
+        assert!(!toml_config.settings.auto_run_suggestion());
+        assert_eq!(toml_config.settings.backups(), 2);
+    }
+
+    #[test]
+    fn test_resolve_state_path() {
+        let state = Some("/state".to_string());
+        assert_eq!(
+            resolve_state_path("config.toml", state.clone()),
+            "config.toml"
+        );
+        if !cfg!(target_os = "windows") {
+            assert_eq!(
+                resolve_state_path("/work/app/.rust_cli.toml", state),
+                "/state/rust_cli/%work%app%.rust_cli.toml"
+            );
+            assert!(resolve_state_path("/app/.rust_cli.toml", None)
+                .ends_with(".local/state/rust_cli/%app%.rust_cli.toml"));
+        }
+    }
+
+    #[test]
+    fn test_find_project_config() {
+        let root = std::env::temp_dir().join(format!("rust_cli_layers_{}", std::process::id()));
+        let nested = root.join("a").join("b");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(find_project_config(&nested), None);
+        std::fs::write(root.join(PROJECT_CONFIG_NAME), "").unwrap();
+        assert_eq!(
+            find_project_config(&nested),
+            Some(root.join(PROJECT_CONFIG_NAME))
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,257 @@
+use crate::utils::{AliasEntry, Error, Scope};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Yaml,
+    #[default]
+    Plain,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct StepRecord {
+    pub run: String,
+    pub continue_on_error: bool,
+}
+
+// An alias as printed by list, show and search. Every field is always
+// present in json and yaml so scripts can rely on the shape.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct AliasRecord {
+    pub group: Option<String>,
+    pub alias: String,
+    pub command: String,
+    pub steps: Vec<StepRecord>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub author: Option<String>,
+    pub env: BTreeMap<String, String>,
+    pub cwd: Option<String>,
+    pub clear_env: bool,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub scope: Option<Scope>,
+    #[serde(skip)]
+    notes: Option<String>,
+}
+
+impl AliasRecord {
+    pub fn new(group: Option<&str>, alias: &str, entry: &AliasEntry, scope: Option<Scope>) -> Self {
+        let table = entry.table().cloned().unwrap_or_default();
+        AliasRecord {
+            group: group.map(str::to_string),
+            alias: alias.to_string(),
+            command: entry.to_string(),
+            steps: entry
+                .steps()
+                .iter()
+                .map(|step| StepRecord {
+                    run: step.run().to_string(),
+                    continue_on_error: step.continue_on_error(),
+                })
+                .collect(),
+            description: table.description,
+            tags: table.tags,
+            author: table.author,
+            env: table.env.into_iter().collect(),
+            cwd: table.cwd,
+            clear_env: table.clear_env,
+            created_at: table.created_at,
+            updated_at: table.updated_at,
+            scope,
+            notes: entry.notes(),
+        }
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.group.clone().unwrap_or_default(),
+            self.alias.clone(),
+            self.command.clone(),
+            self.tags.join(","),
+            self.scope
+                .map(|scope| scope.to_string())
+                .unwrap_or_default(),
+        ]
+    }
+
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        let optional = [
+            ("group", self.group.clone()),
+            ("alias", Some(self.alias.clone())),
+            ("command", Some(self.command.clone())),
+            ("description", self.description.clone()),
+            (
+                "tags",
+                Some(self.tags.join(",")).filter(|tags| !tags.is_empty()),
+            ),
+            ("author", self.author.clone()),
+            ("cwd", self.cwd.clone()),
+            ("clear_env", Some(self.clear_env.to_string())),
+            ("created_at", self.created_at.clone()),
+            ("updated_at", self.updated_at.clone()),
+            ("scope", self.scope.map(|scope| scope.to_string())),
+        ];
+        let mut fields: Vec<_> = optional
+            .into_iter()
+            .filter_map(|(label, value)| Some((label, value?)))
+            .collect();
+        fields.extend(
+            self.env
+                .iter()
+                .map(|(key, value)| ("env", format!("{}={}", key, value))),
+        );
+        fields
+    }
+}
+
+impl fmt::Display for AliasRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.alias, self.command)?;
+        if let Some(scope) = self.scope {
+            write!(f, " [{}]", scope)?;
+        }
+        if let Some(notes) = &self.notes {
+            write!(f, "  # {}", notes)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct GroupRecord {
+    pub group: String,
+    pub aliases: usize,
+}
+
+// Columns padded to their widest cell, separated by two spaces.
+pub fn render_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let header: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+    let mut table = String::new();
+    for row in std::iter::once(&header).chain(rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        table.push_str(line.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+// Json and yaml output; the plain and table formats are laid out by the
+// caller.
+pub fn serialize_output<T: Serialize + ?Sized>(
+    value: &T,
+    output: OutputFormat,
+) -> Result<String, Error> {
+    match output {
+        OutputFormat::Yaml => {
+            serde_yaml_ng::to_string(value).map_err(|err| Error::Output(err.to_string()))
+        }
+        _ => serde_json::to_string_pretty(value)
+            .map(|json| json + "\n")
+            .map_err(|err| Error::Output(err.to_string())),
+    }
+}
+
+pub fn render_records(records: &[AliasRecord], output: OutputFormat) -> Result<String, Error> {
+    match output {
+        OutputFormat::Plain => Ok(records
+            .iter()
+            .map(|record| format!("{}\n", record))
+            .collect()),
+        OutputFormat::Table => {
+            let rows: Vec<Vec<String>> = records.iter().map(AliasRecord::row).collect();
+            Ok(render_table(
+                &["GROUP", "ALIAS", "COMMAND", "TAGS", "SCOPE"],
+                &rows,
+            ))
+        }
+        OutputFormat::Json | OutputFormat::Yaml => serialize_output(records, output),
+    }
+}
+
+pub fn render_record(record: &AliasRecord, output: OutputFormat) -> Result<String, Error> {
+    match output {
+        OutputFormat::Plain => Ok(format!("{}\n", record)),
+        OutputFormat::Table => {
+            let rows: Vec<Vec<String>> = record
+                .fields()
+                .into_iter()
+                .map(|(label, value)| vec![label.to_string(), value])
+                .collect();
+            Ok(render_table(&["FIELD", "VALUE"], &rows))
+        }
+        OutputFormat::Json | OutputFormat::Yaml => serialize_output(record, output),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn records() -> Vec<AliasRecord> {
+        let entry: AliasEntry = toml::from_str::<std::collections::HashMap<String, AliasEntry>>(
+            r#"
+                deploy = { command = "make deploy", tags = ["ops"], env = { B = "2", A = "1" } }
+            "#,
+        )
+        .unwrap()
+        .remove("deploy")
+        .unwrap();
+        vec![
+            AliasRecord::new(None, "ll", &AliasEntry::from("ls -al"), Some(Scope::User)),
+            AliasRecord::new(Some("ops"), "deploy", &entry, None),
+        ]
+    }
+
+    #[test]
+    fn test_render_records_plain() {
+        assert_eq!(
+            render_records(&records(), OutputFormat::Plain).unwrap(),
+            "ll: ls -al [user]\ndeploy: make deploy  # [ops]\n"
+        );
+    }
+
+    #[test]
+    fn test_render_records_table() {
+        assert_eq!(
+            render_records(&records(), OutputFormat::Table).unwrap(),
+            "GROUP  ALIAS   COMMAND      TAGS  SCOPE\n       \
+             ll      ls -al             user\n\
+             ops    deploy  make deploy  ops\n"
+        );
+    }
+
+    #[test]
+    fn test_render_records_json() {
+        let json = render_records(&records(), OutputFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[0]["alias"], "ll");
+        assert_eq!(value[0]["group"], serde_json::Value::Null);
+        assert_eq!(value[0]["scope"], "user");
+        assert_eq!(value[1]["steps"][0]["run"], "make deploy");
+        assert_eq!(value[1]["tags"][0], "ops");
+        assert!(json.find("\"A\"").unwrap() < json.find("\"B\"").unwrap());
+    }
+
+    #[test]
+    fn test_render_record_yaml() {
+        let yaml = render_record(&records().remove(0), OutputFormat::Yaml).unwrap();
+        assert!(yaml.starts_with("group: null\nalias: ll\ncommand: ls -al\n"));
+        assert!(yaml.contains("scope: user\n"));
+    }
+}
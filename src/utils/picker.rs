@@ -0,0 +1,159 @@
+use crate::utils::{highlight, MatchMode, Matcher};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::io::{self, IsTerminal, Write};
+
+pub struct PickerItem {
+    pub label: String,
+    pub preview: String,
+}
+
+// Indexes of the items matching `query`, best fuzzy score first. An empty
+// query keeps every item in its original order.
+pub fn filter_items(items: &[PickerItem], query: &str) -> Vec<usize> {
+    if query.is_empty() {
+        return (0..items.len()).collect();
+    }
+    let Ok(matcher) = Matcher::new(query, MatchMode::Fuzzy) else {
+        return Vec::new();
+    };
+    let mut scored: Vec<(i64, usize)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| Some((matcher.find(&item.label)?.score, index)))
+        .collect();
+    scored.sort_by_key(|(score, index)| (-score, *index));
+    scored.into_iter().map(|(_, index)| index).collect()
+}
+
+struct RawMode;
+
+impl RawMode {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stderr(), EnterAlternateScreen, Hide)?;
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = execute!(io::stderr(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn draw(items: &[PickerItem], query: &str, matches: &[usize], selected: usize) -> io::Result<()> {
+    let mut out = io::stderr();
+    let (width, height) = terminal::size()?;
+    let width = width as usize;
+    let rows = (height as usize).saturating_sub(3).max(1);
+    let offset = selected.saturating_sub(rows - 1);
+    queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+    queue!(
+        out,
+        Print(format!("> {}  ({}/{})", query, matches.len(), items.len()))
+    )?;
+    let matcher = Matcher::new(query, MatchMode::Fuzzy).ok();
+    for (row, &index) in matches.iter().skip(offset).take(rows).enumerate() {
+        let label: String = items[index]
+            .label
+            .chars()
+            .take(width.saturating_sub(2))
+            .collect();
+        let ranges = matcher
+            .as_ref()
+            .and_then(|matcher| matcher.find(&label))
+            .map(|m| m.ranges)
+            .unwrap_or_default();
+        queue!(out, MoveTo(0, row as u16 + 1))?;
+        if offset + row == selected {
+            queue!(out, SetAttribute(Attribute::Reverse), Print("> "))?;
+            queue!(out, Print(&label), SetAttribute(Attribute::Reset))?;
+        } else {
+            queue!(out, Print("  "), Print(highlight(&label, &ranges, true)))?;
+        }
+    }
+    if let Some(&index) = matches.get(selected) {
+        let preview: String = items[index].preview.chars().take(width).collect();
+        queue!(
+            out,
+            MoveTo(0, height.saturating_sub(1)),
+            SetAttribute(Attribute::Dim),
+            Print(preview),
+            SetAttribute(Attribute::Reset)
+        )?;
+    }
+    out.flush()
+}
+
+// Lets the user narrow `items` down by typing and returns the index of the
+// chosen one, or `None` when the picker is cancelled.
+pub fn pick(items: &[PickerItem]) -> io::Result<Option<usize>> {
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return Err(io::Error::other("not a terminal, pass --alias"));
+    }
+    let _raw_mode = RawMode::enter()?;
+    let mut query = String::new();
+    let mut selected = 0;
+    loop {
+        let matches = filter_items(items, &query);
+        selected = selected.min(matches.len().saturating_sub(1));
+        draw(items, &query, &matches, selected)?;
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        else {
+            continue;
+        };
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+        match code {
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if ctrl => return Ok(None),
+            KeyCode::Enter => return Ok(matches.get(selected).copied()),
+            KeyCode::Up => selected = selected.saturating_sub(1),
+            KeyCode::Char('p') if ctrl => selected = selected.saturating_sub(1),
+            KeyCode::Down => selected += 1,
+            KeyCode::Char('n') if ctrl => selected += 1,
+            KeyCode::Backspace => {
+                query.pop();
+                selected = 0;
+            }
+            KeyCode::Char(c) if !ctrl => {
+                query.push(c);
+                selected = 0;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn items(labels: &[&str]) -> Vec<PickerItem> {
+        labels
+            .iter()
+            .map(|label| PickerItem {
+                label: label.to_string(),
+                preview: String::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_items() {
+        let items = items(&["ls", "aws/aws_help", "aws/aws_version", "gcp/help"]);
+        assert_eq!(filter_items(&items, ""), vec![0, 1, 2, 3]);
+        assert_eq!(filter_items(&items, "awsv"), vec![2]);
+        assert_eq!(filter_items(&items, "help").len(), 2);
+        assert!(filter_items(&items, "docker").is_empty());
+    }
+}
@@ -0,0 +1,342 @@
+use crate::utils::Error;
+use clap::ValueEnum;
+use regex::{Regex, RegexBuilder};
+use std::ops::Range;
+
+const MAX_SUGGESTIONS: usize = 3;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum MatchMode {
+    #[default]
+    Substring,
+    Regex,
+    Fuzzy,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    pub score: i64,
+    pub ranges: Vec<Range<usize>>,
+}
+
+// Matching is case-insensitive unless the pattern contains an uppercase
+// letter, the same "smart case" rule used by most fuzzy finders.
+pub struct Matcher {
+    mode: MatchMode,
+    pattern: String,
+    ignore_case: bool,
+    regex: Option<Regex>,
+}
+
+impl Matcher {
+    pub fn new(pattern: &str, mode: MatchMode) -> Result<Self, Error> {
+        let ignore_case = !pattern.chars().any(char::is_uppercase);
+        let regex = match mode {
+            MatchMode::Regex => Some(
+                RegexBuilder::new(pattern)
+                    .case_insensitive(ignore_case)
+                    .build()
+                    .map_err(|err| Error::InvalidPattern(err.to_string()))?,
+            ),
+            _ => None,
+        };
+        Ok(Matcher {
+            mode,
+            pattern: pattern.to_string(),
+            ignore_case,
+            regex,
+        })
+    }
+
+    pub fn find(&self, text: &str) -> Option<Match> {
+        match self.mode {
+            MatchMode::Substring => self.find_substring(text),
+            MatchMode::Regex => {
+                let ranges: Vec<Range<usize>> = self
+                    .regex
+                    .as_ref()?
+                    .find_iter(text)
+                    .filter(|m| !m.is_empty())
+                    .map(|m| m.range())
+                    .collect();
+                if ranges.is_empty() {
+                    None
+                } else {
+                    Some(Match { score: 0, ranges })
+                }
+            }
+            MatchMode::Fuzzy => self.find_fuzzy(text),
+        }
+    }
+
+    fn chars_eq(&self, a: char, b: char) -> bool {
+        if self.ignore_case {
+            a.to_lowercase().eq(b.to_lowercase())
+        } else {
+            a == b
+        }
+    }
+
+    fn match_at(&self, text: &str, start: usize) -> Option<usize> {
+        let mut chars = text[start..].char_indices();
+        let mut end = start;
+        for p in self.pattern.chars() {
+            let (i, c) = chars.next()?;
+            if !self.chars_eq(c, p) {
+                return None;
+            }
+            end = start + i + c.len_utf8();
+        }
+        Some(end)
+    }
+
+    fn find_substring(&self, text: &str) -> Option<Match> {
+        if self.pattern.is_empty() {
+            return None;
+        }
+        let mut ranges = Vec::new();
+        let mut next = 0;
+        for (i, _) in text.char_indices() {
+            if i < next {
+                continue;
+            }
+            if let Some(end) = self.match_at(text, i) {
+                ranges.push(i..end);
+                next = end;
+            }
+        }
+        if ranges.is_empty() {
+            None
+        } else {
+            Some(Match { score: 0, ranges })
+        }
+    }
+
+    fn find_fuzzy(&self, text: &str) -> Option<Match> {
+        let mut pattern = self.pattern.chars().peekable();
+        pattern.peek()?;
+        let mut score = 0;
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        let mut prev: Option<char> = None;
+        for (i, c) in text.char_indices() {
+            let Some(&p) = pattern.peek() else {
+                break;
+            };
+            if self.chars_eq(c, p) {
+                score += 1;
+                if prev.is_none_or(|prev| " _-./:@".contains(prev)) {
+                    score += 3;
+                }
+                match ranges.last_mut() {
+                    Some(last) if last.end == i => {
+                        last.end = i + c.len_utf8();
+                        score += 5;
+                    }
+                    Some(_) => {
+                        score -= 1;
+                        ranges.push(i..i + c.len_utf8());
+                    }
+                    None => ranges.push(i..i + c.len_utf8()),
+                }
+                pattern.next();
+            }
+            prev = Some(c);
+        }
+        if pattern.peek().is_some() {
+            None
+        } else {
+            Some(Match { score, ranges })
+        }
+    }
+}
+
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+// Candidates within a third of the name's length, closest first.
+pub fn suggest<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = (&'a str, String)>,
+) -> Vec<(usize, String)> {
+    let limit = (name.chars().count() / 3).max(1);
+    let mut suggestions: Vec<(usize, String)> = candidates
+        .into_iter()
+        .map(|(candidate, label)| (edit_distance(name, candidate), label))
+        .filter(|(distance, _)| *distance <= limit)
+        .collect();
+    suggestions.sort();
+    suggestions.dedup();
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchHit {
+    pub group: Option<String>,
+    pub alias: String,
+    pub command: String,
+    pub tags: Vec<String>,
+    pub score: i64,
+    pub alias_match: Option<Match>,
+    pub command_match: Option<Match>,
+    pub tag_matches: Vec<Option<Match>>,
+}
+
+impl SearchHit {
+    pub fn render(&self, color: bool) -> String {
+        let ranges = |m: &Option<Match>| m.as_ref().map(|m| m.ranges.clone()).unwrap_or_default();
+        let mut line = String::new();
+        if let Some(group) = &self.group {
+            line.push_str(group);
+            line.push('/');
+        }
+        line.push_str(&highlight(&self.alias, &ranges(&self.alias_match), color));
+        line.push_str(": ");
+        line.push_str(&highlight(
+            &self.command,
+            &ranges(&self.command_match),
+            color,
+        ));
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self
+                .tags
+                .iter()
+                .zip(&self.tag_matches)
+                .map(|(tag, m)| highlight(tag, &ranges(m), color))
+                .collect();
+            line.push_str(&format!(" [{}]", tags.join(", ")));
+        }
+        line
+    }
+}
+
+pub fn highlight(text: &str, ranges: &[Range<usize>], color: bool) -> String {
+    let (open, close) = if color {
+        ("\x1b[1;33m", "\x1b[0m")
+    } else {
+        ("[", "]")
+    };
+    let mut output = String::new();
+    let mut last = 0;
+    for range in ranges {
+        output.push_str(&text[last..range.start]);
+        output.push_str(open);
+        output.push_str(&text[range.clone()]);
+        output.push_str(close);
+        last = range.end;
+    }
+    output.push_str(&text[last..]);
+    output
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod test {
+    use super::*;
+
+    fn ranges(pattern: &str, mode: MatchMode, text: &str) -> Option<Vec<Range<usize>>> {
+        Matcher::new(pattern, mode)
+            .unwrap()
+            .find(text)
+            .map(|m| m.ranges)
+    }
+
+    #[test]
+    fn test_substring() {
+        assert_eq!(
+            ranges("aws", MatchMode::Substring, "aws --AWS"),
+            Some(vec![0..3, 6..9])
+        );
+        assert_eq!(
+            ranges("AWS", MatchMode::Substring, "aws --AWS"),
+            Some(vec![6..9])
+        );
+        assert_eq!(ranges("gcp", MatchMode::Substring, "aws"), None);
+    }
+
+    #[test]
+    fn test_regex() {
+        assert_eq!(
+            ranges(r"--\w+", MatchMode::Regex, "aws --help --version"),
+            Some(vec![4..10, 11..20])
+        );
+        assert!(matches!(
+            Matcher::new("(", MatchMode::Regex),
+            Err(Error::InvalidPattern(_))
+        ));
+    }
+
+    #[test]
+    fn test_fuzzy() {
+        assert_eq!(
+            ranges("awh", MatchMode::Fuzzy, "aws_help"),
+            Some(vec![0..2, 4..5])
+        );
+        assert_eq!(ranges("hwa", MatchMode::Fuzzy, "aws_help"), None);
+        let matcher = Matcher::new("ah", MatchMode::Fuzzy).unwrap();
+        let word_start = matcher.find("aws_help").unwrap().score;
+        let scattered = matcher.find("xaxxxhx").unwrap().score;
+        assert!(word_start > scattered);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("aws_help", "aws_help"), 0);
+        assert_eq!(edit_distance("aws_hlp", "aws_help"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "ls"), 2);
+    }
+
+    #[test]
+    fn test_suggest() {
+        let candidates = ["ls", "ll", "aws_help", "aws_version"]
+            .into_iter()
+            .map(|name| (name, name.to_string()));
+        assert_eq!(
+            suggest("lss", candidates.clone()),
+            vec![(1, "ls".to_string())]
+        );
+        assert_eq!(
+            suggest("aws_hepl", candidates.clone()),
+            vec![(2, "aws_help".to_string())]
+        );
+        assert!(suggest("docker", candidates).is_empty());
+    }
+
+    #[test]
+    fn test_search_hit_render() {
+        let matcher = Matcher::new("aws", MatchMode::Substring).unwrap();
+        let hit = SearchHit {
+            group: Some("aws".to_string()),
+            alias: "aws_help".to_string(),
+            command: "aws --help".to_string(),
+            tags: vec!["cli".to_string(), "aws".to_string()],
+            score: 0,
+            alias_match: matcher.find("aws_help"),
+            command_match: None,
+            tag_matches: vec![None, matcher.find("aws")],
+        };
+        assert_eq!(hit.render(false), "aws/[aws]_help: aws --help [cli, [aws]]");
+    }
+
+    #[test]
+    fn test_highlight() {
+        assert_eq!(highlight("aws --help", &[6..10], false), "aws --[help]");
+        assert_eq!(
+            highlight("aws --help", &[0..3], true),
+            "\x1b[1;33maws\x1b[0m --help"
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use clap::ValueEnum;
+use std::borrow::Cow;
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum ShellKind {
+    Bash,
+    Zsh,
+    Fish,
+    Nu,
+}
+
+fn is_safe(arg: &str) -> bool {
+    !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-+=@%:,./".contains(c))
+}
+
+pub fn quote(arg: &str) -> Cow<'_, str> {
+    if is_safe(arg) {
+        Cow::Borrowed(arg)
+    } else if cfg!(target_os = "windows") {
+        Cow::Owned(format!("\"{}\"", arg.replace('"', "\"\"")))
+    } else {
+        Cow::Owned(posix_quote(arg))
+    }
+}
+
+// Escapes `arg` for use inside a double-quoted string.
+pub fn escape_double_quoted(arg: &str) -> String {
+    if cfg!(target_os = "windows") {
+        return arg.replace('"', "\"\"");
+    }
+    let mut escaped = String::with_capacity(arg.len());
+    for c in arg.chars() {
+        if matches!(c, '\\' | '"' | '$' | '`') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn posix_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+impl ShellKind {
+    pub fn quote(self, arg: &str) -> String {
+        if is_safe(arg) {
+            return arg.to_string();
+        }
+        match self {
+            ShellKind::Bash | ShellKind::Zsh => posix_quote(arg),
+            ShellKind::Fish => format!("'{}'", arg.replace('\\', "\\\\").replace('\'', "\\'")),
+            ShellKind::Nu => {
+                let mut hashes = "#".to_string();
+                while arg.contains(&format!("'{}", hashes)) {
+                    hashes.push('#');
+                }
+                format!("r{}'{}'{}", hashes, arg, hashes)
+            }
+        }
+    }
+
+    // A definition that runs `command` through the shell with any arguments
+    // appended, like a plain shell alias.
+    pub fn alias(self, name: &str, command: &str) -> String {
+        match self {
+            ShellKind::Bash | ShellKind::Zsh => format!("alias {}={}", name, self.quote(command)),
+            ShellKind::Fish => format!("alias {} {}", name, self.quote(command)),
+            ShellKind::Nu => format!(
+                "def --wrapped {} [...args] {{ ^sh -c {} {} ...$args }}",
+                name,
+                self.quote(&format!("{} \"$@\"", command)),
+                name
+            ),
+        }
+    }
+
+    // A function that runs `program` with `args` followed by its own arguments.
+    pub fn function(self, name: &str, program: &str, args: &[&str]) -> String {
+        let mut call = program.to_string();
+        for arg in args {
+            call.push(' ');
+            call.push_str(&self.quote(arg));
+        }
+        match self {
+            ShellKind::Bash | ShellKind::Zsh => format!("{}() {{ {} \"$@\"; }}", name, call),
+            ShellKind::Fish => format!("function {}\n    {} $argv\nend", name, call),
+            ShellKind::Nu => format!("def --wrapped {} [...args] {{ ^{} ...$args }}", name, call),
+        }
+    }
+}
+
+pub fn is_shell_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c))
+}
+
+fn split_words(line: &str, shell: ShellKind) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '#' if !in_word => break,
+            ';' | '&' | '|' | '<' | '>' | '`' | '(' | ')' => {
+                return Err(format!("unsupported shell syntax '{}'", c));
+            }
+            '\\' => {
+                in_word = true;
+                word.push(chars.next().ok_or("line continuations are not supported")?);
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next().ok_or("unterminated single quote")? {
+                        '\'' => break,
+                        '\\' if shell == ShellKind::Fish
+                            && matches!(chars.peek(), Some('\\' | '\'')) =>
+                        {
+                            word.extend(chars.next());
+                        }
+                        c => word.push(c),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next().ok_or("unterminated double quote")? {
+                        '"' => break,
+                        '\\' if matches!(chars.peek(), Some('"' | '\\' | '$' | '`')) => {
+                            word.extend(chars.next());
+                        }
+                        c => word.push(c),
+                    }
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+// Parses an `alias` line from a shell rc file into `(name, command)` pairs.
+// Returns `None` for lines that do not define aliases.
+pub fn parse_alias_line(
+    line: &str,
+    shell: ShellKind,
+) -> Option<Result<Vec<(String, String)>, String>> {
+    let line = line.trim_start();
+    let rest = line.strip_prefix("alias")?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let words = match split_words(rest, shell) {
+        Ok(words) if words.is_empty() => return None,
+        Ok(words) => words,
+        Err(err) => return Some(Err(err)),
+    };
+    if shell == ShellKind::Fish && !words[0].contains('=') {
+        // A single word is the whole command; several are its arguments and
+        // are quoted again for the shell that runs it.
+        let command = match &words[1..] {
+            [command] => command.clone(),
+            args => args
+                .iter()
+                .map(|arg| quote(arg))
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        if command.is_empty() {
+            return Some(Err(format!("alias '{}' has no command", words[0])));
+        }
+        return Some(Ok(vec![(words[0].clone(), command)]));
+    }
+    let definitions = words
+        .iter()
+        .map(|word| {
+            if word.starts_with('-') {
+                return Err(format!("unsupported option '{}'", word));
+            }
+            match word.split_once('=') {
+                Some((name, command)) if !name.is_empty() => {
+                    Ok((name.to_string(), command.to_string()))
+                }
+                _ => Err(format!("'{}' is not an alias definition", word)),
+            }
+        })
+        .collect();
+    Some(definitions)
+}
+
+pub fn append_args(command: &str, args: &[String]) -> String {
+    let mut command = command.to_string();
+    for arg in args {
+        command.push(' ');
+        command.push_str(&quote(arg));
+    }
+    command
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_quote_safe() {
+        assert_eq!(quote("/var/log"), "/var/log");
+        assert_eq!(quote("--region=us-west-2"), "--region=us-west-2");
+    }
+
+    #[test]
+    fn test_quote_unsafe() {
+        if !cfg!(target_os = "windows") {
+            assert_eq!(quote(""), "''");
+            assert_eq!(quote("a b"), "'a b'");
+            assert_eq!(quote("it's $HOME"), "'it'\\''s $HOME'");
+        }
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(ShellKind::Bash.quote("it's"), "'it'\\''s'");
+        assert_eq!(ShellKind::Fish.quote("it's \\n"), "'it\\'s \\\\n'");
+        assert_eq!(ShellKind::Nu.quote("it's"), "r#'it's'#");
+        assert_eq!(ShellKind::Nu.quote("a'#b"), "r##'a'#b'##");
+    }
+
+    #[test]
+    fn test_shell_definitions() {
+        assert_eq!(ShellKind::Zsh.alias("ll", "ls -al"), "alias ll='ls -al'");
+        assert_eq!(ShellKind::Fish.alias("ll", "ls -al"), "alias ll 'ls -al'");
+        assert_eq!(
+            ShellKind::Nu.alias("ll", "ls -al"),
+            "def --wrapped ll [...args] { ^sh -c r#'ls -al \"$@\"'# ll ...$args }"
+        );
+        let args = ["alias", "exec", "-a", "aws/help"];
+        assert_eq!(
+            ShellKind::Bash.function("help", "rust_cli", &args),
+            "help() { rust_cli alias exec -a aws/help \"$@\"; }"
+        );
+        assert_eq!(
+            ShellKind::Fish.function("help", "rust_cli", &args),
+            "function help\n    rust_cli alias exec -a aws/help $argv\nend"
+        );
+        assert!(is_shell_name("aws_help"));
+        assert!(!is_shell_name("my alias"));
+    }
+
+    fn parse(line: &str, shell: ShellKind) -> Option<Result<Vec<(String, String)>, String>> {
+        parse_alias_line(line, shell)
+    }
+
+    fn definition(name: &str, command: &str) -> Option<Result<Vec<(String, String)>, String>> {
+        Some(Ok(vec![(name.to_string(), command.to_string())]))
+    }
+
+    #[test]
+    fn test_parse_alias_line() {
+        assert_eq!(
+            parse("alias ll='ls -al'", ShellKind::Bash),
+            definition("ll", "ls -al")
+        );
+        assert_eq!(
+            parse("  alias gs=\"git status\"  # status", ShellKind::Zsh),
+            definition("gs", "git status")
+        );
+        assert_eq!(
+            parse(r#"alias say='echo '\''hi'\'' "$USER"'"#, ShellKind::Bash),
+            definition("say", r#"echo 'hi' "$USER""#)
+        );
+        assert_eq!(
+            parse(r#"alias q="echo \"\$HOME\"""#, ShellKind::Bash),
+            definition("q", r#"echo "$HOME""#)
+        );
+        assert_eq!(
+            parse("alias a=ls b='ls -l'", ShellKind::Bash),
+            Some(Ok(vec![
+                ("a".to_string(), "ls".to_string()),
+                ("b".to_string(), "ls -l".to_string()),
+            ]))
+        );
+        assert_eq!(parse("export PATH=/bin", ShellKind::Bash), None);
+        assert_eq!(parse("aliases=1", ShellKind::Bash), None);
+        assert!(matches!(
+            parse("alias x='ls", ShellKind::Bash),
+            Some(Err(_))
+        ));
+        assert!(matches!(
+            parse("alias -g G='| grep'", ShellKind::Zsh),
+            Some(Err(_))
+        ));
+        assert!(matches!(
+            parse("alias x=ls; alias y=ls", ShellKind::Bash),
+            Some(Err(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_fish_alias_line() {
+        assert_eq!(
+            parse("alias ll 'ls -al'", ShellKind::Fish),
+            definition("ll", "ls -al")
+        );
+        assert_eq!(
+            parse("alias ll ls -al", ShellKind::Fish),
+            definition("ll", "ls -al")
+        );
+        assert_eq!(
+            parse(r"alias say 'echo \'hi\''", ShellKind::Fish),
+            definition("say", "echo 'hi'")
+        );
+        assert_eq!(
+            parse(r#"alias gc git commit -m "wip work""#, ShellKind::Fish),
+            definition("gc", "git commit -m 'wip work'")
+        );
+        assert_eq!(
+            parse("alias ll=ls", ShellKind::Fish),
+            definition("ll", "ls")
+        );
+        assert!(matches!(parse("alias ll", ShellKind::Fish), Some(Err(_))));
+    }
+
+    #[test]
+    fn test_append_args() {
+        let args = vec!["/var/log".to_string(), "x y".to_string()];
+        if !cfg!(target_os = "windows") {
+            assert_eq!(append_args("ls -al", &args), "ls -al /var/log 'x y'");
+        }
+        assert_eq!(append_args("ls -al", &[]), "ls -al");
+    }
+}
@@ -0,0 +1,462 @@
+use crate::utils::{append_args, escape_double_quoted, quote};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TemplateArgs {
+    pub positional: Vec<String>,
+    pub named: HashMap<String, String>,
+    pub trailing: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TemplateError {
+    Missing(Vec<String>),
+    TooManyValues { expected: usize, given: usize },
+    TrailingArgs,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Missing(names) => {
+                let names: Vec<String> = names.iter().map(|name| format!("{{{}}}", name)).collect();
+                write!(f, "missing values for placeholders: {}", names.join(", "))
+            }
+            TemplateError::TooManyValues { expected, given } => write!(
+                f,
+                "alias takes {} positional value(s) but {} were given",
+                expected, given
+            ),
+            TemplateError::TrailingArgs => write!(
+                f,
+                "trailing arguments cannot be appended to a multi-step alias"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Key {
+    Position(usize),
+    Name(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Placeholder {
+        key: Key,
+        default: Option<String>,
+        double_quoted: bool,
+    },
+}
+
+// A stored command with `{1}`, `{name}` and `{name:-default}` placeholders.
+// In a command that has placeholders or an escaped one such as `{{name}}`,
+// `{{` and `}}` outside single quotes produce literal braces; any other
+// command, say one with a go template, is run exactly as written.
+// Shell syntax such as `${VAR}` or `{a,b}` that does not look like a
+// placeholder is left untouched, as is anything inside single quotes, such
+// as an awk program, a jq filter or a go template.
+// Values given on the command line are quoted for the shell so they stay
+// one argument; defaults are part of the command and go in as written.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut quote: Option<char> = None;
+        let mut escaped = false;
+        let mut rest = source;
+        while let Some(c) = rest.chars().next() {
+            if quote != Some('\'') && (rest.starts_with("{{") || rest.starts_with("}}")) {
+                escaped |= c == '{'
+                    && !literal.ends_with('$')
+                    && parse_placeholder(&rest[1..], false)
+                        .is_some_and(|(_, len)| rest[1 + len..].starts_with('}'));
+                literal.push(c);
+                rest = &rest[2..];
+                continue;
+            }
+            match c {
+                '\\' if quote != Some('\'') => {
+                    let len = rest.chars().take(2).map(char::len_utf8).sum();
+                    literal.push_str(&rest[..len]);
+                    rest = &rest[len..];
+                    continue;
+                }
+                '\'' | '"' if quote.is_none() => quote = Some(c),
+                _ if quote == Some(c) => quote = None,
+                _ => {}
+            }
+            if c == '{' && quote != Some('\'') && !literal.ends_with('$') {
+                if let Some((segment, len)) = parse_placeholder(rest, quote == Some('"')) {
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(segment);
+                    rest = &rest[len..];
+                    continue;
+                }
+            }
+            literal.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        let template = Template { segments };
+        if template.has_placeholders() || escaped || source.is_empty() {
+            return template;
+        }
+        Template {
+            segments: vec![Segment::Literal(source.to_string())],
+        }
+    }
+
+    pub fn has_placeholders(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|segment| matches!(segment, Segment::Placeholder { .. }))
+    }
+
+    pub fn positional_count(&self) -> usize {
+        self.segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Placeholder {
+                    key: Key::Position(index),
+                    ..
+                } => Some(*index),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn render(&self, args: &TemplateArgs) -> Result<String, TemplateError> {
+        let mut output = String::new();
+        let mut missing: Vec<String> = Vec::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => output.push_str(text),
+                Segment::Placeholder {
+                    key,
+                    default,
+                    double_quoted,
+                } => {
+                    let value = match key {
+                        Key::Position(index) => args.positional.get(index - 1),
+                        Key::Name(name) => args.named.get(name),
+                    };
+                    match (value, default) {
+                        (Some(value), _) if *double_quoted => {
+                            output.push_str(&escape_double_quoted(value))
+                        }
+                        (Some(value), _) => output.push_str(&quote(value)),
+                        (None, Some(default)) => output.push_str(default),
+                        (None, None) => {
+                            let name = match key {
+                                Key::Position(index) => index.to_string(),
+                                Key::Name(name) => name.clone(),
+                            };
+                            if !missing.contains(&name) {
+                                missing.push(name);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        if missing.is_empty() {
+            Ok(output)
+        } else {
+            Err(TemplateError::Missing(missing))
+        }
+    }
+}
+
+// Escapes the braces in a shell command so that, stored as a template, it
+// runs exactly as written.
+pub fn escape_template(command: &str) -> String {
+    if command.is_empty()
+        || Template::parse(command).segments == [Segment::Literal(command.to_string())]
+    {
+        return command.to_string();
+    }
+    let mut escaped = String::with_capacity(command.len());
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if quote != Some('\'') => {
+                escaped.push(c);
+                escaped.extend(chars.next());
+                continue;
+            }
+            '\'' | '"' if quote.is_none() => quote = Some(c),
+            _ if quote == Some(c) => quote = None,
+            '{' | '}' if quote != Some('\'') => escaped.push(c),
+            _ => {}
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+pub fn expand_all(commands: &[String], args: &TemplateArgs) -> Result<Vec<String>, TemplateError> {
+    if commands.len() > 1 && !args.trailing.is_empty() {
+        return Err(TemplateError::TrailingArgs);
+    }
+    let templates: Vec<Template> = commands.iter().map(|c| Template::parse(c)).collect();
+    let expected = templates
+        .iter()
+        .map(Template::positional_count)
+        .max()
+        .unwrap_or(0);
+    if args.positional.len() > expected {
+        return Err(TemplateError::TooManyValues {
+            expected,
+            given: args.positional.len(),
+        });
+    }
+    let mut expanded = Vec::new();
+    let mut missing: Vec<String> = Vec::new();
+    for template in &templates {
+        match template.render(args) {
+            Ok(command) => expanded.push(append_args(&command, &args.trailing)),
+            Err(TemplateError::Missing(names)) => {
+                for name in names {
+                    if !missing.contains(&name) {
+                        missing.push(name);
+                    }
+                }
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    if missing.is_empty() {
+        Ok(expanded)
+    } else {
+        Err(TemplateError::Missing(missing))
+    }
+}
+
+fn parse_placeholder(source: &str, double_quoted: bool) -> Option<(Segment, usize)> {
+    let end = source.find('}')?;
+    let body = &source[1..end];
+    let (name, default) = match body.split_once(":-") {
+        Some((name, default)) => (name, Some(default.to_string())),
+        None => (body, None),
+    };
+    let key = if !name.is_empty() && name.chars().all(|c| c.is_ascii_digit()) {
+        match name.parse::<usize>() {
+            Ok(index) if index > 0 => Key::Position(index),
+            _ => return None,
+        }
+    } else if is_identifier(name) {
+        Key::Name(name.to_string())
+    } else {
+        return None;
+    };
+    Some((
+        Segment::Placeholder {
+            key,
+            default,
+            double_quoted,
+        },
+        end + 1,
+    ))
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn expand(command: &str, args: &TemplateArgs) -> Result<String, TemplateError> {
+        let mut commands = expand_all(&[command.to_string()], args)?;
+        Ok(commands.remove(0))
+    }
+
+    fn args(positional: &[&str], named: &[(&str, &str)]) -> TemplateArgs {
+        TemplateArgs {
+            positional: positional.iter().map(|s| s.to_string()).collect(),
+            named: named
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            trailing: vec![],
+        }
+    }
+
+    #[test]
+    fn test_expand_positional_and_named() {
+        let result = expand(
+            "echo {1} --task {task} {2}",
+            &args(&["a", "b"], &[("task", "t1")]),
+        );
+        assert_eq!(result.unwrap(), "echo a --task t1 b");
+    }
+
+    #[test]
+    fn test_expand_default() {
+        let command = "aws --region {region:-us-west-2}";
+        assert_eq!(
+            expand(command, &args(&[], &[])).unwrap(),
+            "aws --region us-west-2"
+        );
+        assert_eq!(
+            expand(command, &args(&[], &[("region", "eu-west-1")])).unwrap(),
+            "aws --region eu-west-1"
+        );
+    }
+
+    #[test]
+    fn test_expand_missing_names_each_placeholder() {
+        let result = expand("run {1} {task} {task} {env}", &args(&[], &[("env", "dev")]));
+        assert_eq!(
+            result.unwrap_err(),
+            TemplateError::Missing(vec!["1".to_string(), "task".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_expand_too_many_values() {
+        let result = expand("echo {1}", &args(&["a", "b"], &[]));
+        assert_eq!(
+            result.unwrap_err(),
+            TemplateError::TooManyValues {
+                expected: 1,
+                given: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_expand_leaves_shell_syntax_alone() {
+        let command = "echo ${HOME} {a,b} {{1}} {1} awk '{print $1}'";
+        assert_eq!(
+            expand(command, &args(&["x"], &[])).unwrap(),
+            "echo ${HOME} {a,b} {1} x awk '{print $1}'"
+        );
+    }
+
+    #[test]
+    fn test_expand_keeps_double_braces() {
+        for command in [
+            "docker ps --format '{{.Names}}'",
+            "kubectl get pods -o go-template=\"{{.metadata.name}}\"",
+            "echo ${{HOME}}",
+        ] {
+            assert_eq!(expand(command, &args(&[], &[])).unwrap(), command);
+        }
+        assert_eq!(
+            expand("echo {{1}} \"{{{{name}}}}\"", &args(&[], &[])).unwrap(),
+            "echo {1} \"{{name}}\""
+        );
+        assert_eq!(
+            expand(
+                "docker ps -f name={1} --format '{{.Names}}'",
+                &args(&["web"], &[])
+            )
+            .unwrap(),
+            "docker ps -f name=web --format '{{.Names}}'"
+        );
+        assert_eq!(
+            expand(
+                "kubectl get {1} -o go-template=\"{{{{.metadata.name}}}}\"",
+                &args(&["pods"], &[])
+            )
+            .unwrap(),
+            "kubectl get pods -o go-template=\"{{.metadata.name}}\""
+        );
+    }
+
+    #[test]
+    fn test_expand_quotes_values() {
+        assert_eq!(
+            expand("echo {1}", &args(&["x y; echo INJECT"], &[])).unwrap(),
+            "echo 'x y; echo INJECT'"
+        );
+        assert_eq!(
+            expand("echo \"hi {name}\"", &args(&[], &[("name", "$(id) \"x\"")])).unwrap(),
+            "echo \"hi \\$(id) \\\"x\\\"\""
+        );
+        assert_eq!(
+            expand("ls {dir:-$HOME/src}", &args(&[], &[])).unwrap(),
+            "ls $HOME/src"
+        );
+    }
+
+    #[test]
+    fn test_expand_skips_single_quotes() {
+        assert_eq!(
+            expand("awk '{print}' {1}", &args(&["file"], &[])).unwrap(),
+            "awk '{print}' file"
+        );
+        assert_eq!(
+            expand("jq '{name}' data.json", &args(&[], &[])).unwrap(),
+            "jq '{name}' data.json"
+        );
+        assert_eq!(
+            expand("echo \\'{1} \"it's {1}\"", &args(&["a"], &[])).unwrap(),
+            "echo \\'a \"it's a\""
+        );
+    }
+
+    #[test]
+    fn test_escape_template() {
+        for command in [
+            "echo {x} ${HOME} '{y}'",
+            "echo \"{{name}}\" {1}",
+            "docker ps --format '{{.Names}}'",
+            "",
+        ] {
+            let escaped = escape_template(command);
+            assert_eq!(expand(&escaped, &args(&[], &[])).unwrap(), command);
+        }
+        assert_eq!(escape_template("echo {x} '{y}'"), "echo {{x}} '{y}'");
+    }
+
+    #[test]
+    fn test_expand_appends_trailing_args() {
+        let mut args = args(&["a"], &[]);
+        args.trailing = vec!["/var/log".to_string()];
+        assert_eq!(expand("ls {1}", &args).unwrap(), "ls a /var/log");
+    }
+
+    #[test]
+    fn test_expand_all_shares_values_across_steps() {
+        let commands = vec!["build {1}".to_string(), "push {1} {tag}".to_string()];
+        assert_eq!(
+            expand_all(&commands, &args(&["app"], &[("tag", "v1")])).unwrap(),
+            vec!["build app", "push app v1"]
+        );
+        assert_eq!(
+            expand_all(&commands, &args(&[], &[])).unwrap_err(),
+            TemplateError::Missing(vec!["1".to_string(), "tag".to_string()])
+        );
+        let mut trailing = args(&["app"], &[("tag", "v1")]);
+        trailing.trailing = vec!["x".to_string()];
+        assert_eq!(
+            expand_all(&commands, &trailing).unwrap_err(),
+            TemplateError::TrailingArgs
+        );
+    }
+}